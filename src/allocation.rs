@@ -0,0 +1,264 @@
+use crate::order::Order;
+use crate::types::{AccountId, Quantity};
+
+/// Allocation decides how an incoming order's quantity is shared among the orders resting at the
+/// price level it matches against. The book asks again whenever a level changes under it, for
+/// example after an iceberg order shows a new peak.
+pub trait Allocation: Send {
+    /// Returns the quantity each of `resting` receives out of `quantity`, in the order of `resting`,
+    /// which is the level's time priority. No order may receive more than its quantity, the total
+    /// may not exceed `quantity`, and an all-or-none order receives all of its quantity or nothing.
+    /// Allocations should be whole multiples of `lot_size` where the quantities allow it.
+    fn allocate(&self, quantity: Quantity, resting: &[Order], lot_size: Quantity) -> Vec<Quantity>;
+
+    /// Whether the strategy fills strictly in time priority. The book then matches the front of
+    /// each queue directly and never asks it to allocate, so fills stay independent of level size.
+    fn is_time_priority(&self) -> bool {
+        false
+    }
+}
+
+/// Fifo fills the orders at a level strictly in time priority, the book's default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fifo;
+
+impl Allocation for Fifo {
+    fn allocate(&self, quantity: Quantity, resting: &[Order], _lot_size: Quantity) -> Vec<Quantity> {
+        let mut allocations = vec![0; resting.len()];
+        fill_in_time_priority(quantity, resting, &mut allocations);
+        allocations
+    }
+
+    fn is_time_priority(&self) -> bool {
+        true
+    }
+}
+
+/// How a pro-rata share is rounded to a whole number of lots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Nearest, // halves round up
+    Up,
+}
+
+impl Rounding {
+    fn divide(&self, numerator: u128, denominator: u128) -> u128 {
+        match self {
+            Rounding::Down => numerator / denominator,
+            Rounding::Nearest => (2 * numerator + denominator) / (2 * denominator),
+            Rounding::Up => numerator.div_ceil(denominator),
+        }
+    }
+}
+
+/// ProRata shares the incoming quantity in proportion to the size of each resting order.
+/// Shares are rounded to lots, and a share below the minimum allocation is dropped.
+/// Whatever the shares leave over is filled in time priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProRata {
+    pub min_allocation: Quantity,
+    pub rounding: Rounding,
+}
+
+impl ProRata {
+    pub fn new(min_allocation: Quantity, rounding: Rounding) -> ProRata {
+        ProRata { min_allocation, rounding }
+    }
+
+    /// Adds the pro-rata shares of `quantity` to the allocations of the resting orders accepted
+    /// by `eligible`, sized by what each still has unallocated, and returns the quantity left over.
+    /// The shares are worked out in time priority, so rounding up cannot take more than `quantity`.
+    fn share(&self, quantity: Quantity, resting: &[Order], allocations: &mut [Quantity], lot_size: Quantity,
+             eligible: impl Fn(&Order) -> bool) -> Quantity {
+        let total: Quantity = resting.iter().zip(allocations.iter())
+            .filter(|(order, _)| eligible(order))
+            .map(|(order, allocation)| order.quantity - allocation)
+            .sum();
+        if total == 0 {
+            return quantity;
+        }
+        let mut left = quantity;
+        for (order, allocation) in resting.iter().zip(allocations.iter_mut()) {
+            if !eligible(order) {
+                continue;
+            }
+            let capacity = order.quantity - *allocation;
+            let lots = self.rounding.divide(quantity as u128 * capacity as u128, total as u128 * lot_size as u128);
+            let share = (lots as Quantity).saturating_mul(lot_size).min(capacity).min(left);
+            if share == 0 || share < self.min_allocation || (order.all_or_none && share < order.quantity) {
+                continue;
+            }
+            *allocation += share;
+            left -= share;
+        }
+        left
+    }
+}
+
+impl Allocation for ProRata {
+    fn allocate(&self, quantity: Quantity, resting: &[Order], lot_size: Quantity) -> Vec<Quantity> {
+        let mut allocations = vec![0; resting.len()];
+        let left = self.share(quantity, resting, &mut allocations, lot_size, |_| true);
+        fill_in_time_priority(left, resting, &mut allocations);
+        allocations
+    }
+}
+
+/// Hybrid allocates pro rata after up to two priority passes. With top-order priority the order at
+/// the front of the queue fills first. Lead market makers then share a percentage of what is left
+/// pro rata among their own orders, and everyone shares the rest pro rata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hybrid {
+    pub top_order: bool,
+    pub market_makers: Vec<AccountId>,
+    pub market_maker_percent: u32,
+    pub pro_rata: ProRata,
+}
+
+impl Hybrid {
+    /// Creates a hybrid with no top-order priority and no market makers, which allocates like `pro_rata`.
+    pub fn new(pro_rata: ProRata) -> Hybrid {
+        Hybrid {
+            top_order: false,
+            market_makers: Vec::new(),
+            market_maker_percent: 0,
+            pro_rata,
+        }
+    }
+
+    pub fn with_top_order(self) -> Hybrid {
+        Hybrid { top_order: true, ..self }
+    }
+
+    /// Gives the orders of `market_makers` `percent` of the quantity left after the top order.
+    pub fn with_market_makers(self, market_makers: &[AccountId], percent: u32) -> Hybrid {
+        assert!(percent <= 100, "market maker percentage must be at most 100");
+        Hybrid {
+            market_makers: market_makers.to_vec(),
+            market_maker_percent: percent,
+            ..self
+        }
+    }
+
+    fn is_market_maker(&self, order: &Order) -> bool {
+        order.owner.is_some_and(|owner| self.market_makers.contains(&owner))
+    }
+}
+
+impl Allocation for Hybrid {
+    fn allocate(&self, quantity: Quantity, resting: &[Order], lot_size: Quantity) -> Vec<Quantity> {
+        let mut allocations = vec![0; resting.len()];
+        let mut left = quantity;
+        if self.top_order && !resting.is_empty() {
+            left = fill_in_time_priority(left, &resting[..1], &mut allocations[..1]);
+        }
+        if self.market_maker_percent > 0 {
+            let quota = left * self.market_maker_percent as Quantity / 100 / lot_size * lot_size;
+            let unallocated = self.pro_rata.share(quota, resting, &mut allocations, lot_size,
+                                                  |order| self.is_market_maker(order));
+            left -= quota - unallocated;
+        }
+        left = self.pro_rata.share(left, resting, &mut allocations, lot_size, |_| true);
+        fill_in_time_priority(left, resting, &mut allocations);
+        allocations
+    }
+}
+
+/// Adds `quantity` to the allocations in time priority, up to each order's quantity, and returns
+/// what is left. An all-or-none order is passed over unless all of it fits.
+fn fill_in_time_priority(mut quantity: Quantity, resting: &[Order], allocations: &mut [Quantity]) -> Quantity {
+    for (order, allocation) in resting.iter().zip(allocations.iter_mut()) {
+        if quantity == 0 {
+            break;
+        }
+        let capacity = order.quantity - *allocation;
+        if order.all_or_none && (*allocation > 0 || capacity > quantity) {
+            continue;
+        }
+        let fill = capacity.min(quantity);
+        *allocation += fill;
+        quantity -= fill;
+    }
+    quantity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrderType, Price, Side};
+
+    fn resting(quantities: &[Quantity]) -> Vec<Order> {
+        quantities.iter().enumerate()
+            .map(|(i, quantity)| Order::new(i as i32 + 1, OrderType::GTC, *quantity, Price::from_int(10), Side::Sell))
+            .collect()
+    }
+
+    #[test]
+    fn fifo_fills_in_time_priority() {
+        assert_eq!(Fifo.allocate(250, &resting(&[100, 200, 300]), 1), vec![100, 150, 0]);
+    }
+
+    #[test]
+    fn fifo_passes_over_all_or_none_orders_that_do_not_fit() {
+        let mut orders = resting(&[100, 200, 50]);
+        orders[1].all_or_none = true;
+        assert_eq!(Fifo.allocate(180, &orders, 1), vec![100, 0, 50]);
+    }
+
+    #[test]
+    fn only_fifo_matches_in_time_priority() {
+        assert!(Fifo.is_time_priority());
+        assert!(!ProRata::new(0, Rounding::Down).is_time_priority());
+        assert!(!Hybrid::new(ProRata::new(0, Rounding::Down)).with_top_order().is_time_priority());
+    }
+
+    #[test]
+    fn pro_rata_rounds_down_and_fills_the_remainder_in_time_priority() {
+        // exact shares are 33.3, 66.6 and 100
+        let allocations = ProRata::new(0, Rounding::Down).allocate(200, &resting(&[100, 200, 300]), 1);
+        assert_eq!(allocations, vec![34, 66, 100]);
+    }
+
+    #[test]
+    fn pro_rata_rounding_rules() {
+        let orders = resting(&[100, 200, 300]);
+        assert_eq!(ProRata::new(0, Rounding::Nearest).allocate(200, &orders, 1), vec![33, 67, 100]);
+        assert_eq!(ProRata::new(0, Rounding::Up).allocate(200, &orders, 1), vec![34, 67, 99]);
+    }
+
+    #[test]
+    fn pro_rata_drops_shares_below_the_minimum_allocation() {
+        // the second order's share of 4 goes to the front of the queue instead
+        let allocations = ProRata::new(5, Rounding::Down).allocate(10, &resting(&[500, 490, 10]), 1);
+        assert_eq!(allocations, vec![10, 0, 0]);
+    }
+
+    #[test]
+    fn pro_rata_allocates_whole_lots() {
+        let allocations = ProRata::new(0, Rounding::Down).allocate(50, &resting(&[30, 30, 40]), 10);
+        assert_eq!(allocations, vec![20, 10, 20]);
+    }
+
+    #[test]
+    fn pro_rata_fills_everyone_when_the_level_is_taken() {
+        let allocations = ProRata::new(5, Rounding::Down).allocate(1000, &resting(&[100, 3, 300]), 1);
+        assert_eq!(allocations, vec![100, 3, 300]);
+    }
+
+    #[test]
+    fn hybrid_top_order_fills_first() {
+        let hybrid = Hybrid::new(ProRata::new(0, Rounding::Down)).with_top_order();
+        // 100 to the top order, then 200 shared over 300 and 100
+        assert_eq!(hybrid.allocate(300, &resting(&[100, 300, 100]), 1), vec![100, 150, 50]);
+    }
+
+    #[test]
+    fn hybrid_market_makers_take_their_percentage_first() {
+        let mut orders = resting(&[100, 100, 200]);
+        orders[2].owner = Some(7);
+        let hybrid = Hybrid::new(ProRata::new(0, Rounding::Down)).with_market_makers(&[7], 40);
+        // 40 to the market maker, then 60 shared over 100, 100 and 160
+        assert_eq!(hybrid.allocate(100, &orders, 1), vec![18, 16, 66]);
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::types::Timestamp;
+
+/// Clock is the book's source of time, used to timestamp trades and to expire orders.
+pub trait Clock: Send {
+    /// Current time in nanoseconds since the Unix epoch.
+    fn now(&self) -> Timestamp;
+}
+
+/// SystemClock reads the time from the operating system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as Timestamp)
+    }
+}
+
+/// SimulatedClock only moves when it is told to, so tests and replays control time exactly.
+/// Clones share the same time, so a clone kept by the caller drives the clock given to a book.
+#[derive(Debug, Default, Clone)]
+pub struct SimulatedClock {
+    now: Arc<AtomicU64>,
+}
+
+impl SimulatedClock {
+    pub fn new(now: Timestamp) -> SimulatedClock {
+        SimulatedClock {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: Timestamp) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, nanos: Timestamp) {
+        self.now.fetch_add(nanos, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Timestamp {
+        self.now.load(Ordering::SeqCst)
+    }
+}
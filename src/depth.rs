@@ -0,0 +1,27 @@
+use crate::order::Order;
+use crate::types::{Price, Quantity};
+
+/// One price level of a depth snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthLevel {
+    pub price: Price,
+    pub quantity: Quantity,        // total displayed quantity
+    pub hidden_quantity: Quantity, // total reserve of iceberg orders at this price
+    pub order_count: usize,
+}
+
+/// Depth is an aggregated (L2) snapshot of the best levels on each side of a book,
+/// best price first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Depth {
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+}
+
+/// A resting order together with its place in the queue of its price level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestingOrder {
+    pub order: Order,             // the live order, with its remaining quantity
+    pub queue_position: usize,    // number of orders ahead of it at its price
+    pub quantity_ahead: Quantity, // displayed quantity of the orders ahead of it
+}
@@ -0,0 +1,55 @@
+use std::fmt;
+
+use crate::types::{Price, Quantity, OrderId};
+
+/// Errors returned by OrderBook operations that refuse a request without touching the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBookError {
+    DuplicateOrderId(OrderId), // an order with this id is already resting on the book
+    UnknownOrderId(OrderId),   // no resting order has this id
+    ZeroQuantity,
+    OffLot(Quantity),           // the quantity is not a multiple of the book's lot size
+    BelowMinQuantity(Quantity),
+    AboveMaxQuantity(Quantity),
+    AboveMaxNotional(Price),    // price times quantity, in price units
+    ZeroDisplayQuantity, // iceberg orders must display part of their quantity
+    InvalidMinExecutionQuantity(Quantity), // must be positive and at most the order quantity
+    AllOrNoneIceberg,    // an all-or-none order cannot hide part of its quantity
+    InvalidPrice(Price), // limit prices must be positive
+    OffTick(Price),      // the price is not a multiple of the book's tick size
+    MissingStopPrice,    // stop orders need a stop price
+    MissingTrail,        // trailing stop orders need a trail
+    InvalidTrail,        // a percentage trail must lie strictly between 0% and 100%
+    MissingExpiry,      // GTD orders need an expiry time
+    ExpiryInPast,       // the expiry time of a GTD order has already passed
+    PostOnlyCannotRest, // only order types that rest on the book can be post-only
+    BookHalted,
+}
+
+impl fmt::Display for OrderBookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderBookError::DuplicateOrderId(id) => write!(f, "order id {} is already on the book", id),
+            OrderBookError::UnknownOrderId(id) => write!(f, "order id {} is not on the book", id),
+            OrderBookError::ZeroQuantity => write!(f, "order quantity must be greater than zero"),
+            OrderBookError::OffLot(quantity) => write!(f, "quantity {} is not a multiple of the lot size", quantity),
+            OrderBookError::BelowMinQuantity(quantity) => write!(f, "quantity {} is below the minimum order size", quantity),
+            OrderBookError::AboveMaxQuantity(quantity) => write!(f, "quantity {} is above the maximum order size", quantity),
+            OrderBookError::AboveMaxNotional(notional) => write!(f, "notional {} is above the maximum order value", notional),
+            OrderBookError::ZeroDisplayQuantity => write!(f, "display quantity must be greater than zero"),
+            OrderBookError::InvalidMinExecutionQuantity(quantity) => write!(f, "invalid minimum execution quantity {}", quantity),
+            OrderBookError::AllOrNoneIceberg => write!(f, "all-or-none orders cannot have a display quantity"),
+            OrderBookError::InvalidPrice(price) => write!(f, "invalid price {}", price),
+            OrderBookError::OffTick(price) => write!(f, "price {} is not a multiple of the tick size", price),
+            OrderBookError::MissingStopPrice => write!(f, "stop orders need a stop price"),
+            OrderBookError::MissingTrail => write!(f, "trailing stop orders need a trail"),
+            OrderBookError::InvalidTrail => write!(f, "percentage trail offsets must lie strictly between 0% and 100%"),
+            OrderBookError::MissingExpiry => write!(f, "GTD orders need an expiry time"),
+            OrderBookError::ExpiryInPast => write!(f, "the expiry time has already passed"),
+            OrderBookError::PostOnlyCannotRest => write!(f, "post-only orders must be able to rest on the book"),
+            OrderBookError::BookHalted => write!(f, "the book is halted"),
+        }
+    }
+}
+
+impl std::error::Error for OrderBookError {}
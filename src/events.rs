@@ -0,0 +1,59 @@
+use crate::trade::TradeId;
+use crate::types::{Price, Quantity, OrderId, Side};
+
+/// Why a resting order lost quantity without trading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    Cancelled,           // cancelled by its owner
+    Amended,             // reduced or moved by an amend
+    Replaced,            // taken off the book by a cancel-replace
+    Expired,             // its time in force ran out
+    SelfTradePrevention, // cancelled to stop a trade with an order of the same owner
+}
+
+/// A change to the visible state of a book. Quantities are displayed quantities, the hidden
+/// reserve of iceberg orders never shows up in events. An iceberg order whose peak is replenished
+/// is executed down to zero and then added again at the back of its queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookEvent {
+    OrderAdded {
+        order_id: OrderId,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    },
+    OrderCancelled {
+        order_id: OrderId,
+        side: Side,
+        price: Price,
+        quantity: Quantity, // quantity taken off the order, which leaves the book once it reaches zero
+        reason: CancelReason,
+    },
+    OrderExecuted {
+        order_id: OrderId,
+        side: Side,
+        price: Price,
+        quantity: Quantity, // quantity traded by the resting order
+    },
+    LevelChanged {
+        side: Side,
+        price: Price,
+        quantity: Quantity, // new total quantity at the level, zero once it is removed
+        order_count: usize,
+    },
+    Trade {
+        trade_id: TradeId,
+        price: Price,
+        quantity: Quantity,
+        buy_order_id: OrderId,
+        sell_order_id: OrderId,
+        aggressor: Side,
+    },
+}
+
+/// A book event with its place in the stream. Sequence numbers start at 1 and have no gaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: BookEvent,
+}
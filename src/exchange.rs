@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::OrderBookError;
+use crate::order::Order;
+use crate::orderbook::OrderBook;
+use crate::report::ExecutionReport;
+use crate::types::{Price, Quantity, OrderId, AccountId, TradingRules};
+
+/// Limit prices an instrument accepts, inclusive at both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceBand {
+    pub low: Price,
+    pub high: Price,
+}
+
+impl PriceBand {
+    pub fn new(low: Price, high: Price) -> PriceBand {
+        PriceBand { low, high }
+    }
+
+    pub fn contains(&self, price: Price) -> bool {
+        self.low <= price && price <= self.high
+    }
+}
+
+/// InstrumentConfig holds the trading rules of one instrument on an Exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstrumentConfig {
+    pub tick_size: Price,
+    pub rules: TradingRules, // lot size and order size limits, enforced by the instrument's book
+    pub price_band: Option<PriceBand>,
+}
+
+impl InstrumentConfig {
+    /// Creates the config of an instrument with no order size limits and no price band.
+    pub fn new(tick_size: Price) -> InstrumentConfig {
+        InstrumentConfig {
+            tick_size,
+            rules: TradingRules::new(),
+            price_band: None,
+        }
+    }
+
+    pub fn with_rules(self, rules: TradingRules) -> InstrumentConfig {
+        InstrumentConfig { rules, ..self }
+    }
+
+    pub fn with_price_band(self, price_band: PriceBand) -> InstrumentConfig {
+        InstrumentConfig {
+            price_band: Some(price_band),
+            ..self
+        }
+    }
+}
+
+/// Errors returned by Exchange operations. Errors of the instrument's book are passed through,
+/// except that an order id the book does not know is reported as `UnknownOrderId`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeError {
+    UnknownSymbol(String),
+    DuplicateSymbol(String),
+    DuplicateOrderId(OrderId), // order ids are unique across every instrument
+    UnknownOrderId(OrderId),
+    OutsidePriceBand(Price),
+    Book(OrderBookError),
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExchangeError::UnknownSymbol(symbol) => write!(f, "no instrument is listed as {}", symbol),
+            ExchangeError::DuplicateSymbol(symbol) => write!(f, "an instrument is already listed as {}", symbol),
+            ExchangeError::DuplicateOrderId(id) => write!(f, "order id {} is already in use", id),
+            ExchangeError::UnknownOrderId(id) => write!(f, "order id {} is not known to the exchange", id),
+            ExchangeError::OutsidePriceBand(price) => write!(f, "price {} is outside the price band", price),
+            ExchangeError::Book(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+impl From<OrderBookError> for ExchangeError {
+    fn from(error: OrderBookError) -> ExchangeError {
+        match error {
+            OrderBookError::UnknownOrderId(id) => ExchangeError::UnknownOrderId(id),
+            error => ExchangeError::Book(error),
+        }
+    }
+}
+
+/// Exchange owns one OrderBook per instrument symbol and routes orders to them.
+/// It keeps an index of every order id it has accepted, so cancels and amends only need the id.
+/// Ids stay in the index once their order is done, so an id is never reused on another instrument.
+#[derive(Default)]
+pub struct Exchange {
+    books: HashMap<String, OrderBook>,
+    configs: HashMap<String, InstrumentConfig>,
+    order_symbols: HashMap<OrderId, String>,
+}
+
+impl Exchange {
+
+    pub fn new() -> Exchange {
+        Exchange {
+            books: HashMap::new(),
+            configs: HashMap::new(),
+            order_symbols: HashMap::new(),
+        }
+    }
+
+    /// Lists a new instrument with an empty book.
+    pub fn add_instrument(&mut self, symbol: &str, config: InstrumentConfig) -> Result<(), ExchangeError> {
+        if self.books.contains_key(symbol) {
+            return Err(ExchangeError::DuplicateSymbol(symbol.to_string()));
+        }
+        let mut book = OrderBook::with_tick_size(config.tick_size);
+        book.set_trading_rules(config.rules);
+        self.books.insert(symbol.to_string(), book);
+        self.configs.insert(symbol.to_string(), config);
+        Ok(())
+    }
+
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol)
+    }
+
+    /// Gives direct access to an instrument's book, for settings such as self-trade prevention.
+    pub fn book_mut(&mut self, symbol: &str) -> Option<&mut OrderBook> {
+        self.books.get_mut(symbol)
+    }
+
+    pub fn config(&self, symbol: &str) -> Option<&InstrumentConfig> {
+        self.configs.get(symbol)
+    }
+
+    /// Iterates over the listed symbols in no particular order.
+    pub fn symbols(&self) -> impl Iterator<Item = &str> + '_ {
+        self.books.keys().map(String::as_str)
+    }
+
+    /// Returns the symbol an order id was placed on.
+    pub fn symbol_of(&self, id: OrderId) -> Option<&str> {
+        self.order_symbols.get(&id).map(String::as_str)
+    }
+
+    /// Checks an order against the instrument's price band and places it on the instrument's book.
+    pub fn place_order(&mut self, symbol: &str, order: Order) -> Result<ExecutionReport, ExchangeError> {
+        let config = *self.configs.get(symbol).ok_or_else(|| ExchangeError::UnknownSymbol(symbol.to_string()))?;
+        if self.order_symbols.contains_key(&order.id) {
+            return Err(ExchangeError::DuplicateOrderId(order.id));
+        }
+        validate_price_band(&config, &order, order.price)?;
+        let report = self.books.get_mut(symbol).unwrap().place_order(order)?;
+        self.order_symbols.insert(order.id, symbol.to_string());
+        Ok(report)
+    }
+
+    /// Cancels an order on whichever instrument it was placed.
+    pub fn cancel_order(&mut self, id: OrderId) -> Result<Order, ExchangeError> {
+        let symbol = self.order_symbols.get(&id).ok_or(ExchangeError::UnknownOrderId(id))?;
+        Ok(self.books.get_mut(symbol).unwrap().cancel_order(id)?)
+    }
+
+    /// Amends an order on whichever instrument it was placed, checking the instrument's price band.
+    pub fn amend_order(&mut self, id: OrderId, new_price: Price, new_quantity: Quantity) -> Result<ExecutionReport, ExchangeError> {
+        let symbol = self.order_symbols.get(&id).ok_or(ExchangeError::UnknownOrderId(id))?;
+        let book = self.books.get_mut(symbol).unwrap();
+        let order = book.get_order(id).ok_or(ExchangeError::UnknownOrderId(id))?.order;
+        validate_price_band(&self.configs[symbol], &order, new_price)?;
+        Ok(book.amend_order(id, new_price, new_quantity)?)
+    }
+
+    /// Returns every open order of `account` across all instruments, with its symbol, sorted by
+    /// symbol. Within each book the resting orders come first in price-time priority, followed by
+    /// the stop orders still waiting for their stop price.
+    pub fn open_orders(&self, account: AccountId) -> Vec<(&str, &Order)> {
+        let mut symbols: Vec<&String> = self.books.keys().collect();
+        symbols.sort();
+        symbols.into_iter()
+            .flat_map(|symbol| {
+                let book = &self.books[symbol];
+                book.bid_orders()
+                    .chain(book.ask_orders())
+                    .chain(book.pending_stops())
+                    .filter(move |order| order.owner == Some(account))
+                    .map(move |order| (symbol.as_str(), order))
+            })
+            .collect()
+    }
+}
+
+fn validate_price_band(config: &InstrumentConfig, order: &Order, price: Price) -> Result<(), ExchangeError> {
+    let band = match config.price_band {
+        Some(band) => band,
+        None => return Ok(()),
+    };
+    if order.kind.has_limit_price() && !band.contains(price) {
+        return Err(ExchangeError::OutsidePriceBand(price));
+    }
+    match order.stop_price {
+        Some(stop_price) if order.kind.is_stop() && !band.contains(stop_price) => {
+            Err(ExchangeError::OutsidePriceBand(stop_price))
+        }
+        _ => Ok(()),
+    }
+}
@@ -5,6 +5,15 @@ pub mod types;
 pub mod orderbook;
 pub mod order;
 pub mod trade;
+pub mod report;
+pub mod error;
+pub mod clock;
+pub mod depth;
+pub mod events;
+pub mod mirror;
+pub mod exchange;
+pub mod allocation;
+mod stops;
 
 pub use orderbook::OrderBook;
 
@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::depth::{Depth, DepthLevel};
+use crate::events::{BookEvent, SequencedEvent};
+use crate::order::{Order, OrderArena, OrderHandle, PriceLevel};
+use crate::types::{Price, Quantity, OrderId, Side, OrderType};
+
+/// A resting order as it appears in a snapshot: only what the market can see of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotOrder {
+    pub order_id: OrderId,
+    pub side: Side,
+    pub price: Price,
+    pub quantity: Quantity, // displayed quantity
+}
+
+/// BookSnapshot is the visible state of a book as of one event sequence number.
+/// Each side lists its orders best price first and in time priority within a price.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookSnapshot {
+    pub sequence: u64, // sequence number of the last event the snapshot includes
+    pub last_trade_price: Option<Price>,
+    pub bids: Vec<SnapshotOrder>,
+    pub asks: Vec<SnapshotOrder>,
+}
+
+/// Errors returned by BookMirror when the event stream cannot be applied. After any of them the
+/// mirror refuses further events until it is resynced from a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorError {
+    SequenceGap { expected: u64, received: u64 }, // an event went missing
+    UnknownOrder(OrderId),                        // an event refers to an order the mirror does not hold
+    QuantityMismatch(OrderId),                    // an event takes more quantity off an order than it has
+    LevelMismatch { side: Side, price: Price },   // the mirror's level disagrees with the source book
+    NeedsResync,
+}
+
+impl fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MirrorError::SequenceGap { expected, received } => {
+                write!(f, "expected event {} but received event {}", expected, received)
+            }
+            MirrorError::UnknownOrder(id) => write!(f, "order id {} is not on the mirror", id),
+            MirrorError::QuantityMismatch(id) => write!(f, "order id {} has less quantity on the mirror", id),
+            MirrorError::LevelMismatch { side, price } => {
+                write!(f, "{:?} level at {} does not match the source book", side, price)
+            }
+            MirrorError::NeedsResync => write!(f, "the mirror needs to be resynced from a snapshot"),
+        }
+    }
+}
+
+impl std::error::Error for MirrorError {}
+
+/// BookMirror is a read-only copy of an OrderBook rebuilt from its event stream.
+/// It keeps every visible order in the same price-time priority as the source book, so it answers
+/// both aggregated (L2) and per-order (L3) queries. Events must arrive in sequence; a missing event
+/// or a level that no longer matches the source stops the mirror until `resync` is called with a
+/// fresh snapshot.
+#[derive(Debug, Default)]
+pub struct BookMirror {
+    orders: OrderArena,
+    order_handles: HashMap<OrderId, OrderHandle>,
+    bids: BTreeMap<Price, PriceLevel>,
+    asks: BTreeMap<Price, PriceLevel>,
+    sequence: u64,
+    last_trade_price: Option<Price>,
+    needs_resync: bool,
+}
+
+impl BookMirror {
+
+    /// Creates a mirror of an empty book that has not recorded any events yet.
+    pub fn new() -> BookMirror {
+        BookMirror {
+            orders: OrderArena::new(),
+            order_handles: HashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            sequence: 0,
+            last_trade_price: None,
+            needs_resync: false,
+        }
+    }
+
+    /// Creates a mirror from a snapshot of the source book.
+    pub fn from_snapshot(snapshot: &BookSnapshot) -> BookMirror {
+        let mut mirror = BookMirror::new();
+        mirror.resync(snapshot);
+        mirror
+    }
+
+    /// Replaces the whole state of the mirror with `snapshot`. Events up to the snapshot's sequence
+    /// number are ignored from then on, so events buffered while waiting for it can be replayed.
+    pub fn resync(&mut self, snapshot: &BookSnapshot) {
+        *self = BookMirror {
+            sequence: snapshot.sequence,
+            last_trade_price: snapshot.last_trade_price,
+            ..BookMirror::new()
+        };
+        for order in snapshot.bids.iter().chain(snapshot.asks.iter()) {
+            self.add_order(order.order_id, order.side, order.price, order.quantity);
+        }
+    }
+
+    /// Applies the next event of the stream. Events the mirror has already seen are ignored.
+    pub fn apply(&mut self, event: &SequencedEvent) -> Result<(), MirrorError> {
+        if self.needs_resync {
+            return Err(MirrorError::NeedsResync);
+        }
+        if event.sequence <= self.sequence {
+            return Ok(());
+        }
+        let result = if event.sequence != self.sequence + 1 {
+            Err(MirrorError::SequenceGap {
+                expected: self.sequence + 1,
+                received: event.sequence,
+            })
+        } else {
+            self.apply_event(&event.event)
+        };
+        match result {
+            Ok(()) => self.sequence = event.sequence,
+            Err(_) => self.needs_resync = true,
+        }
+        result
+    }
+
+    /// Applies a batch of events in order, stopping at the first one that fails.
+    pub fn apply_all<'a>(&mut self, events: impl IntoIterator<Item = &'a SequencedEvent>) -> Result<(), MirrorError> {
+        events.into_iter().try_for_each(|event| self.apply(event))
+    }
+
+    fn apply_event(&mut self, event: &BookEvent) -> Result<(), MirrorError> {
+        match *event {
+            BookEvent::OrderAdded { order_id, side, price, quantity } => {
+                self.add_order(order_id, side, price, quantity);
+            }
+            BookEvent::OrderCancelled { order_id, quantity, .. }
+            | BookEvent::OrderExecuted { order_id, quantity, .. } => {
+                self.reduce_order(order_id, quantity)?;
+            }
+            BookEvent::LevelChanged { side, price, quantity, order_count } => {
+                let level = self.levels(side).get(&price).map_or((0, 0), |level| (level.quantity, level.len()));
+                if level != (quantity, order_count) {
+                    return Err(MirrorError::LevelMismatch { side, price });
+                }
+            }
+            BookEvent::Trade { price, .. } => self.last_trade_price = Some(price),
+        }
+        Ok(())
+    }
+
+    fn add_order(&mut self, id: OrderId, side: Side, price: Price, quantity: Quantity) {
+        let handle = self.orders.insert(Order::new(id, OrderType::GTC, quantity, price, side));
+        self.order_handles.insert(id, handle);
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        levels.entry(price).or_insert_with(PriceLevel::new).push(&mut self.orders, handle);
+    }
+
+    fn reduce_order(&mut self, id: OrderId, quantity: Quantity) -> Result<(), MirrorError> {
+        let handle = *self.order_handles.get(&id).ok_or(MirrorError::UnknownOrder(id))?;
+        let order = self.orders.get_mut(handle);
+        order.quantity = order.quantity.checked_sub(quantity).ok_or(MirrorError::QuantityMismatch(id))?;
+        let (side, price, filled) = (order.side, order.price, order.quantity == 0);
+
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.get_mut(&price).unwrap();
+        level.quantity -= quantity;
+        if filled {
+            level.queue.remove(&mut self.orders, handle);
+            self.orders.remove(handle);
+            self.order_handles.remove(&id);
+        }
+        if level.is_empty() {
+            levels.remove(&price);
+        }
+        Ok(())
+    }
+
+    /// Sequence number of the last event applied to the mirror.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns true once the mirror has stopped applying events and waits for a snapshot.
+    pub fn needs_resync(&self) -> bool {
+        self.needs_resync
+    }
+
+    pub fn last_trade_price(&self) -> Option<Price> {
+        self.last_trade_price
+    }
+
+    pub fn get_bid(&self) -> Option<&Price> {
+        self.bids.keys().next_back()
+    }
+
+    pub fn get_ask(&self) -> Option<&Price> {
+        self.asks.keys().next()
+    }
+
+    /// Returns a mirrored order with its displayed quantity.
+    pub fn get_order(&self, id: OrderId) -> Option<&Order> {
+        self.order_handles.get(&id).map(|handle| self.orders.get(*handle))
+    }
+
+    /// Returns the best `levels` price levels of each side, like `OrderBook::depth`.
+    /// Hidden quantity never reaches the mirror, so it is always zero.
+    pub fn depth(&self, levels: usize) -> Depth {
+        Depth {
+            bids: self.bid_depth().take(levels).collect(),
+            asks: self.ask_depth().take(levels).collect(),
+        }
+    }
+
+    pub fn bid_depth(&self) -> impl Iterator<Item = DepthLevel> + '_ {
+        self.bids.iter().rev().map(|(price, level)| depth_level(*price, level))
+    }
+
+    pub fn ask_depth(&self) -> impl Iterator<Item = DepthLevel> + '_ {
+        self.asks.iter().map(|(price, level)| depth_level(*price, level))
+    }
+
+    /// Iterates over every mirrored bid, best price first and in time priority within a price.
+    pub fn bid_orders(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.bids.values().rev().flat_map(|level| level.queue.iter(&self.orders))
+    }
+
+    /// Iterates over every mirrored ask, best price first and in time priority within a price.
+    pub fn ask_orders(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.asks.values().flat_map(|level| level.queue.iter(&self.orders))
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<Price, PriceLevel> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+}
+
+fn depth_level(price: Price, level: &PriceLevel) -> DepthLevel {
+    DepthLevel {
+        price,
+        quantity: level.quantity,
+        hidden_quantity: 0,
+        order_count: level.len(),
+    }
+}
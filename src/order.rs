@@ -1,10 +1,19 @@
 use crate::types::{Price, Quantity, OrderId, 
-                   Side, OrderType};
-use std::collections::VecDeque;
+                   Side, OrderType, AccountId, Trail, PostOnly, Timestamp};
 use std::cmp::Ordering;
 
 /// Order is a struct that represents an order.
 /// An order has an id, a type, a quantity, a price, and a side (buy or sell).
+/// It may also carry the account that owns it, which the book uses for self-trade prevention,
+/// the stop price that stop orders wait for before they are placed, and the trail along which
+/// trailing stop orders move their stop price.
+/// An iceberg order has a display quantity: once it rests, `quantity` is the displayed peak and
+/// `hidden_quantity` the reserve behind it, which the book uses to replenish the peak.
+/// A post-only order only ever adds liquidity to the book, and a GTD order carries the time it
+/// expires at.
+/// An order with a minimum execution quantity only trades on arrival if at least that much fills
+/// straight away. An all-or-none order must fill completely, and once it rests it is skipped by
+/// incoming orders too small to fill all of it.
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub struct Order {
     pub id: OrderId,
@@ -12,6 +21,15 @@ pub struct Order {
     pub quantity: Quantity,
     pub price: Price,
     pub side: Side,
+    pub owner: Option<AccountId>,
+    pub stop_price: Option<Price>,
+    pub trail: Option<Trail>,
+    pub display_quantity: Option<Quantity>,
+    pub hidden_quantity: Quantity,
+    pub post_only: Option<PostOnly>,
+    pub expires_at: Option<Timestamp>,
+    pub min_execution_quantity: Option<Quantity>,
+    pub all_or_none: bool,
 }
 
 impl PartialOrd for Order {
@@ -28,11 +46,89 @@ impl Order {
     //! Create a new order
     pub fn new(id: OrderId, kind: OrderType, quantity: Quantity, price: Price, side: Side) -> Order {
         Order {
-            id: id,
-            kind: kind,
-            quantity: quantity,
-            price: price,
-            side: side,
+            id,
+            kind,
+            quantity,
+            price,
+            side,
+            owner: None,
+            stop_price: None,
+            trail: None,
+            display_quantity: None,
+            hidden_quantity: 0,
+            post_only: None,
+            expires_at: None,
+            min_execution_quantity: None,
+            all_or_none: false,
+        }
+    }
+
+    /// Sets the price a stop order waits for.
+    pub fn with_stop_price(self, stop_price: Price) -> Order {
+        Order {
+            stop_price: Some(stop_price),
+            ..self
+        }
+    }
+
+    /// Sets how a trailing stop order follows the market.
+    pub fn with_trail(self, trail: Trail) -> Order {
+        Order {
+            trail: Some(trail),
+            ..self
+        }
+    }
+
+    /// Makes the order an iceberg that shows at most `display_quantity` on the book.
+    pub fn with_display_quantity(self, display_quantity: Quantity) -> Order {
+        Order {
+            display_quantity: Some(display_quantity),
+            ..self
+        }
+    }
+
+    /// Makes the order post-only, with `mode` deciding what happens if it would take liquidity.
+    pub fn with_post_only(self, mode: PostOnly) -> Order {
+        Order {
+            post_only: Some(mode),
+            ..self
+        }
+    }
+
+    /// Sets the time a GTD order expires at.
+    pub fn with_expiry(self, expires_at: Timestamp) -> Order {
+        Order {
+            expires_at: Some(expires_at),
+            ..self
+        }
+    }
+
+    /// Sets the quantity that must fill as soon as the order arrives for it to trade at all.
+    pub fn with_min_execution_quantity(self, min_execution_quantity: Quantity) -> Order {
+        Order {
+            min_execution_quantity: Some(min_execution_quantity),
+            ..self
+        }
+    }
+
+    /// Makes the order all-or-none, so it is only ever filled completely.
+    pub fn with_all_or_none(self) -> Order {
+        Order {
+            all_or_none: true,
+            ..self
+        }
+    }
+
+    /// Returns the displayed and hidden quantity together.
+    pub fn open_quantity(&self) -> Quantity {
+        self.quantity + self.hidden_quantity
+    }
+
+    /// Sets the account that owns the order.
+    pub fn with_owner(self, owner: AccountId) -> Order {
+        Order {
+            owner: Some(owner),
+            ..self
         }
     }
 
@@ -40,46 +136,212 @@ impl Order {
         if self.side == Side::Buy {
             self.price
         } else {
-            Price(-self.price.0)
+            -self.price
         }
     }
 }
 
+/// Index of an order's slot in an OrderArena.
+pub(crate) type OrderHandle = usize;
+
+/// OrderNode is a resting order together with the links to its neighbours in its OrderQueue.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OrderNode {
+    pub(crate) order: Order,
+    prev: Option<OrderHandle>,
+    next: Option<OrderHandle>,
+}
+
+/// OrderArena is a slab holding every resting order of a book.
+/// Freed slots are reused, so handles stay small and stable for as long as the order rests.
+#[derive(Debug, Default)]
+pub(crate) struct OrderArena {
+    slots: Vec<Option<OrderNode>>,
+    free: Vec<OrderHandle>,
+}
+
+impl OrderArena {
+
+    pub(crate) fn new() -> OrderArena {
+        OrderArena {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, order: Order) -> OrderHandle {
+        let node = OrderNode { order, prev: None, next: None };
+        match self.free.pop() {
+            Some(handle) => {
+                self.slots[handle] = Some(node);
+                handle
+            }
+            None => {
+                self.slots.push(Some(node));
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    /// Frees the slot of an order. The order must already be unlinked from its queue.
+    pub(crate) fn remove(&mut self, handle: OrderHandle) -> Order {
+        let node = self.slots[handle].take().unwrap();
+        self.free.push(handle);
+        node.order
+    }
+
+    pub(crate) fn get(&self, handle: OrderHandle) -> &Order {
+        &self.node(handle).order
+    }
+
+    pub(crate) fn get_mut(&mut self, handle: OrderHandle) -> &mut Order {
+        &mut self.node_mut(handle).order
+    }
+
+    fn node(&self, handle: OrderHandle) -> &OrderNode {
+        self.slots[handle].as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, handle: OrderHandle) -> &mut OrderNode {
+        self.slots[handle].as_mut().unwrap()
+    }
+}
+
 /// OrderQueue is a queue of orders with the same price.
 /// It is used to store orders with the same price in the order book.
-/// The order queue contains a double ended queue, but only implements methods of a queue.
+/// The orders live in the book's OrderArena, the queue only links their handles into a doubly
+/// linked list, so an order can be unlinked from anywhere in the queue in constant time.
 /// The order queue is ordered by the time the orders were added to the queue.
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) struct OrderQueue(pub(crate) VecDeque<OrderId>);
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct OrderQueue {
+    head: Option<OrderHandle>,
+    tail: Option<OrderHandle>,
+    len: usize,
+}
 
 impl OrderQueue {
 
     pub(crate) fn new() -> OrderQueue {
-        OrderQueue(VecDeque::new())
+        OrderQueue {
+            head: None,
+            tail: None,
+            len: 0,
+        }
     }
 
-    pub(crate) fn push(&mut self, id: OrderId) {
-        self.0.push_back(id);
+    pub(crate) fn push(&mut self, arena: &mut OrderArena, handle: OrderHandle) {
+        let node = arena.node_mut(handle);
+        node.prev = self.tail;
+        node.next = None;
+        match self.tail {
+            Some(tail) => arena.node_mut(tail).next = Some(handle),
+            None => self.head = Some(handle),
+        }
+        self.tail = Some(handle);
+        self.len += 1;
     }
 
-    pub(crate) fn pop(&mut self) -> Option<OrderId> {
-        self.0.pop_front()
+    pub(crate) fn pop(&mut self, arena: &mut OrderArena) -> Option<OrderHandle> {
+        let head = self.head?;
+        self.remove(arena, head);
+        Some(head)
     }
 
-    pub(crate) fn peek(&self) -> Option<&OrderId> {
-        self.0.front()
+    pub(crate) fn peek(&self) -> Option<OrderHandle> {
+        self.head
     }
 
     pub(crate) fn len(&self) -> usize {
-        self.0.len()
+        self.len
     }
 
     pub(crate) fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.len == 0
+    }
+
+    /// Unlinks an order from the queue. The order stays in the arena.
+    pub(crate) fn remove(&mut self, arena: &mut OrderArena, handle: OrderHandle) {
+        let node = arena.node_mut(handle);
+        let (prev, next) = (node.prev, node.next);
+        node.prev = None;
+        node.next = None;
+        match prev {
+            Some(prev) => arena.node_mut(prev).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => arena.node_mut(next).prev = prev,
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+    }
+
+    /// Returns the order queued right behind `handle`.
+    pub(crate) fn next(&self, arena: &OrderArena, handle: OrderHandle) -> Option<OrderHandle> {
+        arena.node(handle).next
+    }
+
+    /// Links an order into the queue just ahead of `next`, or at the back if `next` is None.
+    pub(crate) fn insert_before(&mut self, arena: &mut OrderArena, handle: OrderHandle, next: Option<OrderHandle>) {
+        let next = match next {
+            Some(next) => next,
+            None => return self.push(arena, handle),
+        };
+        let prev = arena.node(next).prev;
+        let node = arena.node_mut(handle);
+        node.prev = prev;
+        node.next = Some(next);
+        arena.node_mut(next).prev = Some(handle);
+        match prev {
+            Some(prev) => arena.node_mut(prev).next = Some(handle),
+            None => self.head = Some(handle),
+        }
+        self.len += 1;
+    }
+
+    /// Iterates over the queued orders in time priority.
+    pub(crate) fn iter<'a>(&self, arena: &'a OrderArena) -> impl Iterator<Item = &'a Order> + 'a {
+        let mut next = self.head;
+        std::iter::from_fn(move || {
+            let node = arena.node(next?);
+            next = node.next;
+            Some(&node.order)
+        })
+    }
+}
+
+/// PriceLevel holds every resting order at one price, in time priority,
+/// together with their total displayed and hidden quantity.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct PriceLevel {
+    pub(crate) queue: OrderQueue,
+    pub(crate) quantity: Quantity,
+    pub(crate) hidden_quantity: Quantity,
+}
+
+impl PriceLevel {
+
+    pub(crate) fn new() -> PriceLevel {
+        PriceLevel {
+            queue: OrderQueue::new(),
+            quantity: 0,
+            hidden_quantity: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, arena: &mut OrderArena, handle: OrderHandle) {
+        self.queue.push(arena, handle);
+        let order = arena.get(handle);
+        self.quantity += order.quantity;
+        self.hidden_quantity += order.hidden_quantity;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queue.len()
     }
 
-    pub(crate) fn remove_order(&mut self, order_id: OrderId) {
-        self.0.retain(|&id| id != order_id);
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.is_empty()
     }
 }
 
@@ -95,8 +357,17 @@ mod tests {
             id: 1,
             kind: OrderType::GTC,
             quantity: 100,
-            price: Price(100.0),
+            price: Price::from_int(100),
             side: Side::Buy,
+            owner: None,
+            stop_price: None,
+            trail: None,
+            display_quantity: None,
+            hidden_quantity: 0,
+            post_only: None,
+            expires_at: None,
+            min_execution_quantity: None,
+            all_or_none: false,
         };
         assert_eq!(1, order.id);
     }
@@ -109,18 +380,57 @@ mod tests {
 
     #[test]
     fn orderqueue_push() {
+        let mut arena = OrderArena::new();
         let mut orderqueue: OrderQueue = OrderQueue::new();
-        orderqueue.push(1);
+        let handle = arena.insert(Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy));
+        orderqueue.push(&mut arena, handle);
         assert_eq!(1, orderqueue.len());
     }
 
     #[test]
     fn orderqueue_pop() {
+        let mut arena = OrderArena::new();
+        let mut orderqueue: OrderQueue = OrderQueue::new();
+        let handle = arena.insert(Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy));
+        orderqueue.push(&mut arena, handle);
+        let popped_order: Option<OrderHandle> = orderqueue.pop(&mut arena);
+        assert_eq!(None, orderqueue.pop(&mut arena));
+        assert_eq!(1, arena.get(popped_order.unwrap()).id);
+    }
+
+    #[test]
+    fn orderqueue_remove_keeps_priority() {
+        let mut arena = OrderArena::new();
         let mut orderqueue: OrderQueue = OrderQueue::new();
-        orderqueue.push(1);
-        let popped_order: Option<OrderId> = orderqueue.pop();
-        assert_eq!(None, orderqueue.pop());
-        assert_eq!(1, popped_order.unwrap());
+        let handles: Vec<OrderHandle> = (1..=4)
+            .map(|id| arena.insert(Order::new(id, OrderType::GTC, 100, Price::from_int(100), Side::Buy)))
+            .collect();
+        for &handle in handles.iter() {
+            orderqueue.push(&mut arena, handle);
+        }
+
+        orderqueue.remove(&mut arena, handles[1]); // middle
+        orderqueue.remove(&mut arena, handles[3]); // tail
+        let ids: Vec<OrderId> = orderqueue.iter(&arena).map(|order| order.id).collect();
+        assert_eq!(vec![1, 3], ids);
+
+        orderqueue.remove(&mut arena, handles[0]); // head
+        orderqueue.push(&mut arena, handles[1]);
+        let ids: Vec<OrderId> = orderqueue.iter(&arena).map(|order| order.id).collect();
+        assert_eq!(vec![3, 2], ids);
+        assert_eq!(2, orderqueue.len());
+    }
+
+    #[test]
+    fn arena_reuses_freed_slots() {
+        let mut arena = OrderArena::new();
+        let first = arena.insert(Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy));
+        let second = arena.insert(Order::new(2, OrderType::GTC, 100, Price::from_int(100), Side::Buy));
+        assert_eq!(1, arena.remove(first).id);
+        let third = arena.insert(Order::new(3, OrderType::GTC, 100, Price::from_int(100), Side::Buy));
+        assert_eq!(first, third);
+        assert_eq!(2, arena.get(second).id);
+        assert_eq!(3, arena.get(third).id);
     }
 
     #[test]
@@ -129,15 +439,33 @@ mod tests {
             id: 1,
             kind: OrderType::GTC,
             quantity: 100,
-            price: Price(100.0),
+            price: Price::from_int(100),
             side: Side::Buy,
+            owner: None,
+            stop_price: None,
+            trail: None,
+            display_quantity: None,
+            hidden_quantity: 0,
+            post_only: None,
+            expires_at: None,
+            min_execution_quantity: None,
+            all_or_none: false,
         };
         let order2: Order = Order {
             id: 1,
             kind: OrderType::GTC,
             quantity: 100,
-            price: Price(100.0),
+            price: Price::from_int(100),
             side: Side::Buy,
+            owner: None,
+            stop_price: None,
+            trail: None,
+            display_quantity: None,
+            hidden_quantity: 0,
+            post_only: None,
+            expires_at: None,
+            min_execution_quantity: None,
+            all_or_none: false,
         };
         assert_eq!(order1, order2);
     }
@@ -1,412 +1,1471 @@
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 
-use crate::order::{Order, OrderQueue};
+use crate::order::{Order, OrderArena, OrderHandle, PriceLevel};
 use crate::types::{Price, Quantity, OrderId, 
-                   Side, OrderType};
-use crate::trade::Trade;
+                   Side, OrderType, Timestamp, SelfTradePrevention,
+                   Trail, TrailOffset, TrailReference, PostOnly, TradingRules};
+use crate::trade::{Trade, TradeId, TradeLog};
+use crate::stops::StopStore;
+use crate::report::{ExecutionReport, Fill, OrderExpired, RejectReason, SelfTradePrevented};
+use crate::clock::{Clock, SystemClock};
+use crate::depth::{Depth, DepthLevel, RestingOrder};
+use crate::events::{BookEvent, CancelReason, SequencedEvent};
+use crate::mirror::{BookSnapshot, SnapshotOrder};
+use crate::error::OrderBookError;
+use crate::allocation::{Allocation, Fifo};
 
 pub struct OrderBook {
-    pub(crate) buy_orders: HashMap<OrderId, Order>,
-    pub(crate) sell_orders: HashMap<OrderId, Order>,
+    pub(crate) orders: OrderArena, // every resting order, linked into the queue of its price level
+    pub(crate) order_handles: HashMap<OrderId, OrderHandle>,
 
-    pub(crate) bid_tree: BinaryHeap<Price>,
-    pub(crate) ask_tree: BinaryHeap<Price>,
-
-    pub(crate) bid_price_map: HashMap<Price, OrderQueue>,
-    pub(crate) ask_price_map: HashMap<Price, OrderQueue>,
+    // one entry per non-empty price level, the best bid is the last key and the best ask the first
+    pub(crate) bids: BTreeMap<Price, PriceLevel>,
+    pub(crate) asks: BTreeMap<Price, PriceLevel>,
 
+    // displayed quantity resting on each side, and the hidden reserve of iceberg orders behind it
     pub buy_volume: Quantity,
     pub sell_volume: Quantity,
+    pub buy_hidden_volume: Quantity,
+    pub sell_hidden_volume: Quantity,
+
+    pub trades: TradeLog,
+    pub(crate) stops: StopStore, // stop orders waiting for their stop price to trade
+    pub(crate) stops_checked_through: TradeId, // last trade the stop orders have been checked against
+    pub(crate) sequence: u64, // increases with every change to the book
+    pub(crate) expiries: BTreeSet<(Timestamp, OrderId)>, // resting GTD orders by expiry time
+    pub(crate) clock: Box<dyn Clock>,
+    pub(crate) allocation: Box<dyn Allocation>, // shares incoming quantity among a level's orders
+
+    pub(crate) record_events: bool,
+    pub(crate) events: Vec<SequencedEvent>, // recorded events not drained yet
+    pub(crate) event_sequence: u64,
 
-    pub trades: HashMap<(OrderId, OrderId), Trade>, // (buy_order_id, sell_order_id) -> Trade
+    pub(crate) tick_size: Price,
+    pub(crate) rules: TradingRules,
+    pub(crate) market_protection_ticks: Option<u32>,
+    pub(crate) self_trade_prevention: Option<SelfTradePrevention>,
+    pub(crate) halted: bool,
+}
+
+impl Default for OrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
+/// Tick size of a book created with `OrderBook::new`, 0.01.
+pub const DEFAULT_TICK_SIZE: Price = Price::from_raw(10_000);
+
 impl OrderBook {
     pub fn new() -> OrderBook {
         OrderBook {
-            buy_orders: HashMap::new(),
-            sell_orders: HashMap::new(),
-
-            bid_tree: BinaryHeap::new(),
-            ask_tree: BinaryHeap::new(),
+            orders: OrderArena::new(),
+            order_handles: HashMap::new(),
 
-            bid_price_map: HashMap::new(),
-            ask_price_map: HashMap::new(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
 
             buy_volume: 0,
             sell_volume: 0,
+            buy_hidden_volume: 0,
+            sell_hidden_volume: 0,
+
+            trades: TradeLog::new(),
+            stops: StopStore::new(),
+            stops_checked_through: 0,
+            sequence: 0,
+            expiries: BTreeSet::new(),
+            clock: Box::new(SystemClock),
+            allocation: Box::new(Fifo),
+
+            record_events: false,
+            events: Vec::new(),
+            event_sequence: 0,
+
+            tick_size: DEFAULT_TICK_SIZE,
+            rules: TradingRules::new(),
+            market_protection_ticks: None,
+            self_trade_prevention: None,
+            halted: false,
+        }
+    }
+
+    /// Create an order book that only accepts prices on multiples of `tick_size`.
+    pub fn with_tick_size(tick_size: Price) -> OrderBook {
+        assert!(tick_size > Price::ZERO, "tick size must be positive");
+        OrderBook {
+            tick_size,
+            ..OrderBook::new()
+        }
+    }
+
+    pub fn tick_size(&self) -> Price {
+        self.tick_size
+    }
+
+    /// Limits how far a market order may walk the book. With `Some(ticks)`, a market order only
+    /// matches up to `ticks` ticks away from the best opposite price at the time it arrives,
+    /// and any quantity left beyond that is cancelled. `None` lets market orders sweep the whole book.
+    pub fn set_market_protection(&mut self, ticks: Option<u32>) {
+        self.market_protection_ticks = ticks;
+    }
+
+    /// Sets the lot size and the order size limits checked when orders are placed or amended.
+    /// An amend that keeps an order's open quantity is not held to the lot size and minimum quantity.
+    pub fn set_trading_rules(&mut self, rules: TradingRules) {
+        assert!(rules.lot_size > 0, "lot size must be positive");
+        self.rules = rules;
+    }
+
+    pub fn trading_rules(&self) -> TradingRules {
+        self.rules
+    }
+
+    /// Sets how the book stops orders of the same owner from trading with each other.
+    /// Orders without an owner never count as a self-trade. `None` lets every order match.
+    pub fn set_self_trade_prevention(&mut self, mode: Option<SelfTradePrevention>) {
+        self.self_trade_prevention = mode;
+    }
+
+    /// Replaces the clock the book timestamps trades with and expires orders by.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Replaces the strategy that shares an incoming order among the orders at a price level.
+    /// Books match in time priority with `Fifo` until told otherwise.
+    pub fn set_allocation(&mut self, allocation: impl Allocation + 'static) {
+        self.allocation = Box::new(allocation);
+    }
+
+    /// Turns the recording of book events on or off. Recorded events wait in the book until
+    /// `drain_events` takes them.
+    pub fn set_event_recording(&mut self, enabled: bool) {
+        self.record_events = enabled;
+    }
 
-            trades: HashMap::new(),
+    /// Takes every event recorded since the last drain, in sequence order.
+    pub fn drain_events(&mut self) -> Vec<SequencedEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Sequence number of the latest recorded event.
+    pub fn event_sequence(&self) -> u64 {
+        self.event_sequence
+    }
+
+    /// Returns the visible state of the book as of the latest recorded event, for starting or
+    /// resyncing a `BookMirror`.
+    pub fn snapshot(&self) -> BookSnapshot {
+        let snapshot_order = |order: &Order| SnapshotOrder {
+            order_id: order.id,
+            side: order.side,
+            price: order.price,
+            quantity: order.quantity,
+        };
+        BookSnapshot {
+            sequence: self.event_sequence,
+            last_trade_price: self.last_trade_price(),
+            bids: self.bid_orders().map(snapshot_order).collect(),
+            asks: self.ask_orders().map(snapshot_order).collect(),
         }
     }
 
-    pub fn place_order(&mut self, order: Order) -> bool { // returns true if order successfully matched
+    /// Stops the book from accepting new orders until `resume` is called.
+    /// Resting orders may still be cancelled while the book is halted.
+    pub fn halt(&mut self) {
+        self.halted = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.halted = false;
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    pub fn place_order(&mut self, order: Order) -> Result<ExecutionReport, OrderBookError> {
+        self.validate_order(&order)?;
+        let expired = self.expire_orders();
+        let mut report = if order.kind.is_stop() {
+            self.place_stop(order)
+        } else {
+            self.execute(order)
+        };
+        report.expired = expired;
+        self.trigger_stops(&mut report);
+        Ok(report)
+    }
+
+    /// Changes the price and/or open quantity of a resting order, following the usual exchange
+    /// priority rules. Reducing the quantity at the same price keeps the order's place in its queue.
+    /// A new price or a larger quantity sends the order to the back of the queue at its new price,
+    /// and an order repriced through the spread is matched as if it had just been placed.
+    /// A post-only order that would be rejected for taking liquidity keeps its current price.
+    /// A GTD order that has reached its expiry time can no longer be amended, even before
+    /// `expire_orders` has removed it.
+    pub fn amend_order(&mut self, id: OrderId, new_price: Price, new_quantity: Quantity) -> Result<ExecutionReport, OrderBookError> {
+        if self.halted {
+            return Err(OrderBookError::BookHalted);
+        }
+        let order = match self.order_handles.get(&id) {
+            Some(handle) => *self.orders.get(*handle),
+            None => return Err(OrderBookError::UnknownOrderId(id)),
+        };
+        let now = self.clock.now();
+        if has_expired(&order, now) {
+            return Err(OrderBookError::UnknownOrderId(id)); // it is reported once the book expires it
+        }
+        let amended = Order {
+            price: new_price,
+            quantity: new_quantity,
+            hidden_quantity: 0,
+            ..order
+        };
+        self.validate_price_and_quantity(&amended)?;
+        if new_quantity != order.open_quantity() {
+            // a partly filled order may be left off-lot or below the minimum, and can still be
+            // repriced as it is
+            self.validate_lot_and_min_quantity(&amended)?;
+        }
+        let expired = self.expire_orders_at(now);
+
+        if new_price == order.price && new_quantity <= order.open_quantity() {
+            self.decrease_order(id, order.open_quantity() - new_quantity, CancelReason::Amended);
+            let mut report = ExecutionReport::new(&amended).finish(new_quantity);
+            report.expired = expired;
+            return Ok(report);
+        }
+        if let Some(reason) = self.arrival_rejection(&amended) {
+            // the original order stays on the book untouched
+            let mut report = ExecutionReport::rejected(&amended, reason);
+            report.expired = expired;
+            return Ok(report);
+        }
+        self.decrease_order(id, order.open_quantity(), CancelReason::Amended);
+        let mut report = self.execute(amended);
+        report.expired = expired;
+        self.trigger_stops(&mut report);
+        Ok(report)
+    }
+
+    /// Atomically cancels a resting order and places `new_order` with its own id in its place.
+    /// If the replacement is refused, or would be rejected on arrival, the original order is left
+    /// untouched. The replacement is checked against the book as it will be without the original.
+    pub fn cancel_replace(&mut self, id: OrderId, new_order: Order) -> Result<ExecutionReport, OrderBookError> {
+        let handle = match self.order_handles.get(&id) {
+            Some(handle) => *handle,
+            None => return Err(OrderBookError::UnknownOrderId(id)),
+        };
+        if self.halted {
+            return Err(OrderBookError::BookHalted);
+        }
+        let now = self.clock.now();
+        if has_expired(self.orders.get(handle), now) {
+            return Err(OrderBookError::UnknownOrderId(id)); // it is reported once the book expires it
+        }
+        self.without_order(handle, |book| book.validate_order(&new_order))?;
+        let mut expired = self.expire_orders_at(now);
+        if let Some(reason) = self.without_order(handle, |book| book.arrival_rejection(&new_order)) {
+            let mut report = ExecutionReport::rejected(&new_order, reason);
+            report.expired = expired;
+            return Ok(report);
+        }
+        let open_quantity = self.orders.get(handle).open_quantity();
+        self.decrease_order(id, open_quantity, CancelReason::Replaced);
+        let mut report = self.place_order(new_order)?;
+        expired.append(&mut report.expired);
+        report.expired = expired;
+        Ok(report)
+    }
+
+    /// Looks at the book as if a resting order had gone, then puts the order back in its place.
+    fn without_order<T>(&mut self, handle: OrderHandle, look: impl FnOnce(&OrderBook) -> T) -> T {
+        let next = self.lift_order(handle);
+        let result = look(self);
+        self.restore_order(handle, next);
+        result
+    }
+
+    /// Unlinks a resting order from its price level without any of the bookkeeping of a cancel.
+    /// Returns the order queued behind it, which `restore_order` needs to put the order back in
+    /// its place.
+    fn lift_order(&mut self, handle: OrderHandle) -> Option<OrderHandle> {
+        let order = *self.orders.get(handle);
+        let levels = match order.side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.get_mut(&order.price).unwrap();
+        let next = level.queue.next(&self.orders, handle);
+        level.queue.remove(&mut self.orders, handle);
+        level.quantity -= order.quantity;
+        level.hidden_quantity -= order.hidden_quantity;
+        if level.is_empty() {
+            levels.remove(&order.price);
+        }
+        next
+    }
+
+    /// Puts an order taken off the book by `lift_order` back ahead of `next`.
+    fn restore_order(&mut self, handle: OrderHandle, next: Option<OrderHandle>) {
+        let order = *self.orders.get(handle);
+        let levels = match order.side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.entry(order.price).or_insert_with(PriceLevel::new);
+        level.queue.insert_before(&mut self.orders, handle, next);
+        level.quantity += order.quantity;
+        level.hidden_quantity += order.hidden_quantity;
+    }
+
+    /// Holds a validated stop order off the book, unless the market has already traded through
+    /// its stop price, in which case it is placed straight away.
+    /// A trailing stop starts at the more favourable of its own stop price and the stop price
+    /// trailing its reference price.
+    fn place_stop(&mut self, order: Order) -> ExecutionReport {
+        let stop_price = self.initial_stop_price(&order);
+        if self.stop_triggered(order.side, stop_price) {
+            return self.execute(activate_stop(order, stop_price));
+        }
+        let sequence = self.next_sequence();
+        self.stops.insert(order, stop_price, sequence);
+        ExecutionReport::pending(&order)
+    }
+
+    /// Returns the stop price a validated stop order starts from.
+    fn initial_stop_price(&self, order: &Order) -> Price {
+        let trailing = order.trail.filter(|_| order.kind.is_trailing()).and_then(|trail| {
+            self.trail_reference(order.side, trail.reference)
+                .map(|reference| trail.stop_price(order.side, reference, self.tick_size))
+        });
+        match (order.stop_price, trailing) {
+            (Some(stop_price), Some(trailing)) => match order.side {
+                Side::Buy => stop_price.min(trailing),
+                Side::Sell => stop_price.max(trailing),
+            },
+            (stop_price, trailing) => stop_price.or(trailing).unwrap(),
+        }
+    }
+
+    /// Checks whether the market has already traded through `stop_price`.
+    fn stop_triggered(&self, side: Side, stop_price: Price) -> bool {
+        self.last_trade_price().is_some_and(|last_price| match side {
+            Side::Buy => last_price >= stop_price,
+            Side::Sell => last_price <= stop_price,
+        })
+    }
+
+    /// Walks the trades since the last check in order. Each trade first moves the trailing stops
+    /// that follow the last trade price, then releases the stops it triggers one at a time in the
+    /// order they arrived. Trades of a released stop are walked in the same pass, so they can
+    /// trigger further stops. Trailing stops that follow the best price move once the book has
+    /// settled. The reports of released stops are added to `report`.
+    fn trigger_stops(&mut self, report: &mut ExecutionReport) {
+        while let Some(price) = self.trades.get(self.stops_checked_through + 1).map(|trade| trade.price) {
+            self.stops_checked_through += 1;
+            self.ratchet_trailing_stops(TrailReference::LastTrade, |_| Some(price));
+            while let Some(id) = self.stops.next_triggered(price, price) {
+                let stop_price = self.stops.stop_price(id).unwrap();
+                let order = self.stops.remove(id).unwrap();
+                let triggered_report = self.execute(activate_stop(order, stop_price));
+                report.triggered.push(triggered_report);
+            }
+        }
+        let (best_bid, best_ask) = (self.get_bid().copied(), self.get_ask().copied());
+        self.ratchet_trailing_stops(TrailReference::BestPrice, |side| match side {
+            Side::Buy => best_ask,
+            Side::Sell => best_bid,
+        });
+    }
+
+    /// Moves the waiting trailing stops that follow `reference` after the reference price for
+    /// their side.
+    fn ratchet_trailing_stops(&mut self, reference: TrailReference, reference_price: impl Fn(Side) -> Option<Price>) {
+        for order in self.stops.trailing() {
+            let trail = order.trail.unwrap();
+            if trail.reference != reference {
+                continue;
+            }
+            if let Some(price) = reference_price(order.side) {
+                self.stops.ratchet(order.id, trail.stop_price(order.side, price, self.tick_size));
+            }
+        }
+    }
+
+    /// The current reference price a trailing stop on `side` follows, if the book has one.
+    fn trail_reference(&self, side: Side, reference: TrailReference) -> Option<Price> {
+        match reference {
+            TrailReference::LastTrade => self.last_trade_price(),
+            TrailReference::BestPrice => self.best_opposite(side),
+        }
+    }
+
+    /// Matches a validated order and rests or cancels whatever is left of it.
+    fn execute(&mut self, mut order: Order) -> ExecutionReport {
+        self.next_sequence();
+        if let Some(reason) = self.arrival_rejection(&order) {
+            return ExecutionReport::rejected(&order, reason);
+        }
+        let mut report = ExecutionReport::new(&order);
+        // a post-only order that would take liquidity slides behind the touch
+        if let Some(price) = self.post_only_price(&order) {
+            order.price = price;
+            report.repriced = Some(price);
+        }
+        let limit = self.match_limit(&order);
+        let remaining_order = self.sweep(order, limit, &mut report);
+        match remaining_order {
+            // IOC and market orders never rest, their unfilled quantity is cancelled
+            Some(remaining) if remaining.kind == OrderType::IOC || remaining.kind == OrderType::Market => {
+                report.cancel(remaining.quantity);
+                report.finish(0)
+            }
+            Some(remaining) => {
+                self.add_order(remaining);
+                report.finish(remaining.quantity)
+            }
+            None => report.finish(0),
+        }
+    }
+
+    /// Returns why an order would be rejected as soon as it arrives, without touching the book:
+    /// a post-only order that cannot avoid taking liquidity, or an order that cannot fill its
+    /// minimum execution quantity. A stop order is looked at as the order it would release if the
+    /// market has already traded through its stop price.
+    fn arrival_rejection(&self, order: &Order) -> Option<RejectReason> {
+        let mut order = *order;
+        if order.kind.is_stop() {
+            let stop_price = self.initial_stop_price(&order);
+            if !self.stop_triggered(order.side, stop_price) {
+                return None;
+            }
+            order = activate_stop(order, stop_price);
+        }
+        if let Some(mode) = order.post_only {
+            if let Some(price) = self.post_only_price(&order) {
+                if mode == PostOnly::Reject || price <= Price::ZERO {
+                    return Some(RejectReason::WouldTakeLiquidity);
+                }
+                order.price = price;
+            }
+        }
+        if self.misses_min_execution(&order, self.match_limit(&order)) {
+            return Some(RejectReason::InsufficientLiquidity);
+        }
+        None
+    }
+
+    /// Returns the price one tick behind the best opposite price if a post-only order would take
+    /// liquidity at its own price.
+    fn post_only_price(&self, order: &Order) -> Option<Price> {
+        order.post_only?;
+        self.crossing_price(order).map(|best_price| match order.side {
+            Side::Buy => best_price - self.tick_size,
+            Side::Sell => best_price + self.tick_size,
+        })
+    }
+
+    /// Returns the worst price an arriving order may match at, or None for no limit.
+    fn match_limit(&self, order: &Order) -> Option<Price> {
+        match order.kind {
+            OrderType::GTC | OrderType::GTD | OrderType::Day | OrderType::FOK | OrderType::IOC => Some(order.price),
+            OrderType::Market => {
+                let best_price = self.best_opposite(order.side);
+                self.market_protection_ticks.zip(best_price).map(|(ticks, best_price)| {
+                    let protection = self.tick_size * ticks as i64;
+                    match order.side {
+                        Side::Buy => best_price + protection,
+                        Side::Sell => best_price - protection,
+                    }
+                })
+            }
+            OrderType::StopMarket | OrderType::StopLimit
+            | OrderType::TrailingStopMarket | OrderType::TrailingStopLimit => {
+                unreachable!("stop orders wait in the stop store until triggered")
+            }
+        }
+    }
+
+    /// Returns the best opposite price if a limit order would match as soon as it arrives.
+    fn crossing_price(&self, order: &Order) -> Option<Price> {
+        self.best_opposite(order.side).filter(|best_price| crosses(order.side, *best_price, Some(order.price)))
+    }
+
+    /// Checks whether an incoming order has to be rejected because less than its minimum execution
+    /// quantity can fill straight away. A fill-or-kill or all-or-none order must fill completely.
+    /// An order that could rest and does not cross the book at all is not held to its minimum.
+    fn misses_min_execution(&self, order: &Order, limit: Option<Price>) -> bool {
+        let min_quantity = match order.kind {
+            OrderType::FOK => order.quantity,
+            _ if order.all_or_none => order.quantity,
+            _ => match order.min_execution_quantity {
+                Some(min_quantity) => min_quantity,
+                None => return false,
+            },
+        };
+        if order.kind.can_rest() && self.crossing_price(order).is_none() {
+            return false;
+        }
+        !self.can_fill(order, min_quantity, limit)
+    }
+
+    /// Checks that an incoming order may be placed on the book at all.
+    fn validate_order(&self, order: &Order) -> Result<(), OrderBookError> {
+        if self.halted {
+            return Err(OrderBookError::BookHalted);
+        }
+        if self.order_handles.contains_key(&order.id) || self.stops.contains(order.id) {
+            return Err(OrderBookError::DuplicateOrderId(order.id));
+        }
+        self.validate_price_and_quantity(order)?;
+        self.validate_lot_and_min_quantity(order)?;
+        if order.kind.is_trailing() && order.stop_price.is_none() {
+            // without a reference price to trail yet, the order needs a stop price to start from
+            let trail = order.trail.unwrap();
+            if self.trail_reference(order.side, trail.reference).is_none() {
+                return Err(OrderBookError::MissingStopPrice);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the fields of an order that must hold both when it is placed and when it is amended.
+    fn validate_price_and_quantity(&self, order: &Order) -> Result<(), OrderBookError> {
+        if order.quantity == 0 {
+            return Err(OrderBookError::ZeroQuantity);
+        }
+        if order.display_quantity == Some(0) {
+            return Err(OrderBookError::ZeroDisplayQuantity);
+        }
+        if let Some(min_quantity) = order.min_execution_quantity {
+            if min_quantity == 0 || min_quantity > order.quantity {
+                return Err(OrderBookError::InvalidMinExecutionQuantity(min_quantity));
+            }
+        }
+        if order.all_or_none && order.display_quantity.is_some() {
+            return Err(OrderBookError::AllOrNoneIceberg);
+        }
+        self.validate_trading_rules(order)?;
+        if order.post_only.is_some() && !order.kind.can_rest() {
+            return Err(OrderBookError::PostOnlyCannotRest);
+        }
+        if order.kind == OrderType::GTD {
+            let expires_at = order.expires_at.ok_or(OrderBookError::MissingExpiry)?;
+            if expires_at <= self.clock.now() {
+                return Err(OrderBookError::ExpiryInPast);
+            }
+        }
+        if order.kind.is_trailing() {
+            let trail = order.trail.ok_or(OrderBookError::MissingTrail)?;
+            self.validate_trail(&trail)?;
+        }
+        if order.kind.is_stop() {
+            match order.stop_price {
+                Some(stop_price) => self.validate_price(stop_price)?,
+                None if !order.kind.is_trailing() => return Err(OrderBookError::MissingStopPrice),
+                None => {}
+            }
+        }
+        if order.kind.has_limit_price() { // market orders ignore their price
+            self.validate_price(order.price)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the lot size and minimum quantity, which apply to new orders and to amends that change
+    /// the quantity.
+    fn validate_lot_and_min_quantity(&self, order: &Order) -> Result<(), OrderBookError> {
+        for quantity in std::iter::once(order.quantity).chain(order.display_quantity) {
+            if !quantity.is_multiple_of(self.rules.lot_size) {
+                return Err(OrderBookError::OffLot(quantity));
+            }
+        }
+        if order.quantity < self.rules.min_quantity {
+            return Err(OrderBookError::BelowMinQuantity(order.quantity));
+        }
+        Ok(())
+    }
+
+    /// Checks the maximum quantity and notional, which apply to every new and amended order.
+    fn validate_trading_rules(&self, order: &Order) -> Result<(), OrderBookError> {
+        if self.rules.max_quantity.is_some_and(|max_quantity| order.quantity > max_quantity) {
+            return Err(OrderBookError::AboveMaxQuantity(order.quantity));
+        }
+        // orders without a limit price have no notional until they trade
+        if let Some(max_notional) = self.rules.max_notional.filter(|_| order.kind.has_limit_price()) {
+            let notional = order.price.raw() as i128 * order.quantity as i128;
+            if notional > max_notional.raw() as i128 {
+                let notional = Price::from_raw(notional.min(i64::MAX as i128) as i64);
+                return Err(OrderBookError::AboveMaxNotional(notional));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_trail(&self, trail: &Trail) -> Result<(), OrderBookError> {
+        match trail.offset {
+            TrailOffset::Amount(amount) => self.validate_price(amount)?,
+            TrailOffset::BasisPoints(bps) if bps == 0 || bps >= 10_000 => {
+                return Err(OrderBookError::InvalidTrail);
+            }
+            TrailOffset::BasisPoints(_) => {}
+        }
+        if trail.limit_offset < Price::ZERO {
+            return Err(OrderBookError::InvalidPrice(trail.limit_offset));
+        }
+        if !trail.limit_offset.is_on_tick(self.tick_size) {
+            return Err(OrderBookError::OffTick(trail.limit_offset));
+        }
+        Ok(())
+    }
+
+    fn validate_price(&self, price: Price) -> Result<(), OrderBookError> {
+        if price <= Price::ZERO {
+            return Err(OrderBookError::InvalidPrice(price));
+        }
+        if !price.is_on_tick(self.tick_size) {
+            return Err(OrderBookError::OffTick(price));
+        }
+        Ok(())
+    }
+
+    /// Matches an incoming order against the opposite side of the book. The book's allocation
+    /// strategy shares the incoming quantity among the orders at the best price level, and the
+    /// shares are matched in time priority before moving on to the next best level, until the
+    /// incoming order is filled or the best opposite price no longer crosses `limit`.
+    /// The level is allocated again whenever a self-trade is prevented or it still holds orders,
+    /// such as iceberg orders showing a new peak, once its shares are matched.
+    /// A `limit` of `None` matches at any price. Fills and prevented self-trades are added to `report`.
+    /// Returns the unfilled remainder, if any.
+    fn sweep(&mut self, mut order: Order, limit: Option<Price>, report: &mut ExecutionReport) -> Option<Order> {
         match order.side {
-            Side::Buy => {
-                self.buy_volume += order.quantity;
-                match order.kind {
-                    OrderType::GTC => { // GTC orders always return true
-                        if self.ask_tree.is_empty() {
-                            self.add_order(order, false);
-                        } else if self.get_ask().unwrap() <= &order.price {
-                            let ask_price = self.get_ask().unwrap().clone();
-                            let sell_order = self.ask_price_map.get_mut(&ask_price).unwrap().pop().unwrap();
-                            let remaining_order = self.match_order(order,
-                                self.sell_orders.get(&sell_order).unwrap().clone(),
-                                Side::Sell);
-                            if let Some(order) = remaining_order {
-                                self.add_order(order, false);
-                            }
-                        } else {
-                            self.add_order(order, false);
-                        }
+            Side::Buy => self.buy_volume += order.quantity,
+            Side::Sell => self.sell_volume += order.quantity,
+        }
+        'levels: while let Some(next_order) = self.next_resting_order(&order, limit) {
+            let mut shares: Vec<(Order, Quantity)> = Vec::new();
+            if !self.allocation.is_time_priority() {
+                let resting_orders: Vec<Order> = self.orders_at(next_order.side, next_order.price).copied().collect();
+                let allocations = self.allocation.allocate(order.quantity, &resting_orders, self.rules.lot_size);
+                shares = resting_orders.into_iter()
+                    .zip(allocations)
+                    .filter(|(_, quantity)| *quantity > 0)
+                    .collect();
+            }
+            if shares.is_empty() {
+                // in time priority, and as a fallback that always makes progress, the front order fills first
+                shares.push((next_order, next_order.quantity));
+            }
+
+            for (resting_order, share) in shares {
+                if let Some(mode) = self.self_trade_mode(&order, &resting_order) {
+                    let prevented = self.prevent_self_trade(mode, &order, &resting_order);
+                    order.quantity -= prevented.incoming_cancelled;
+                    match order.side {
+                        Side::Buy => self.buy_volume -= prevented.incoming_cancelled,
+                        Side::Sell => self.sell_volume -= prevented.incoming_cancelled,
                     }
-                    OrderType::FOK => {return false;}
-                    OrderType::IOC => {return false;}
-                    OrderType::Market => {
-                        if self.ask_tree.is_empty() {
-                            return false;
-                        } else {
-                            let ask_price = self.get_ask().unwrap().clone();
-                            let sell_order = self.ask_price_map.get_mut(&ask_price).unwrap().pop().unwrap();
-                            let remaining_order = self.match_order(order,
-                                self.sell_orders.get(&sell_order).unwrap().clone(),
-                                Side::Sell);
-                            if let Some(order) = remaining_order {
-                                self.add_order(order, false);
-                            }
-                        }
+                    report.prevent_self_trade(prevented);
+                    if order.quantity == 0 {
+                        return None;
                     }
+                    continue 'levels;
+                }
+
+                let quantity = share.min(order.quantity).min(resting_order.quantity);
+                match order.side {
+                    Side::Buy => self.match_order(order, resting_order, Side::Sell, quantity),
+                    Side::Sell => self.match_order(resting_order, order, Side::Buy, quantity),
+                }
+                report.fill(Fill {
+                    counterparty: resting_order.id,
+                    price: resting_order.price,
+                    quantity,
+                });
+                order.quantity -= quantity;
+                if order.quantity == 0 {
+                    return None;
                 }
             }
-            Side::Sell => {
-                self.sell_volume += order.quantity;
-                match order.kind {
-                    OrderType::GTC => {
-                        if self.bid_tree.is_empty() {
-                            self.add_order(order, false);
-                        } else if self.get_bid().unwrap() >= &order.price {
-                            let bid_price = self.get_bid().unwrap().clone();
-                            let buy_order = self.bid_price_map.get_mut(&bid_price).unwrap().pop().unwrap();
-                            let remaining_order = self.match_order(self.buy_orders.get(&buy_order).unwrap().clone(),
-                            order,
-                            Side::Buy);
-                            if let Some(order) = remaining_order {
-                                self.add_order(order, false);
-                            }
-                        } else {
-                            self.add_order(order, false);
-                        }
+        }
+        // the unfilled remainder is only counted again if it rests on the book
+        match order.side {
+            Side::Buy => self.buy_volume -= order.quantity,
+            Side::Sell => self.sell_volume -= order.quantity,
+        }
+        Some(order)
+    }
+
+    /// Returns the resting order `order` matches against next: the first in price-time priority at
+    /// a price crossing `limit`, passing over all-or-none orders larger than what is left of `order`.
+    /// The orders passed over keep their place in their queue.
+    fn next_resting_order(&self, order: &Order, limit: Option<Price>) -> Option<Order> {
+        for (price, level) in self.levels_from_best(order.side.opposite()) {
+            if !crosses(order.side, *price, limit) {
+                break;
+            }
+            let resting_order = level.queue.iter(&self.orders)
+                .find(|resting_order| !resting_order.all_or_none || resting_order.open_quantity() <= order.quantity);
+            if let Some(resting_order) = resting_order {
+                return Some(*resting_order);
+            }
+        }
+        None
+    }
+
+    /// Checks whether at least `quantity` of `order` would fill straight away against the opposite
+    /// side of the book at prices crossing `limit`. Used to decide whether an order with a minimum
+    /// execution quantity executes at all.
+    fn can_fill(&self, order: &Order, quantity: Quantity, limit: Option<Price>) -> bool {
+        let mut available: Quantity = 0;
+        for (price, level) in self.levels_from_best(order.side.opposite()) {
+            if !crosses(order.side, *price, limit) {
+                break;
+            }
+            let (wanted, needed) = (order.quantity - available, quantity - available);
+            let filled = if self.allocation.is_time_priority() {
+                self.time_priority_fill(order, level, wanted, needed)
+            } else {
+                self.allocated_fill(order, level, wanted, needed)
+            };
+            match filled {
+                Some(filled) => available += filled,
+                None => return false, // the incoming order would lose quantity without trading
+            }
+            if available >= quantity {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns how much of `wanted` the orders at one level would fill in time priority, stopping
+    /// once `needed` is reached. As in `sweep`, an iceberg order fills its current peak where it
+    /// stands and each new peak from the back of the level. Returns None if a self-trade would
+    /// cancel part of the incoming `order` first.
+    fn time_priority_fill(&self, order: &Order, level: &PriceLevel, wanted: Quantity, needed: Quantity) -> Option<Quantity> {
+        let mut filled: Quantity = 0;
+        let mut queue = level.queue.iter(&self.orders);
+        let mut replenished: VecDeque<Order> = VecDeque::new();
+        while let Some(resting_order) = queue.next().copied().or_else(|| replenished.pop_front()) {
+            match self.self_trade_mode(order, &resting_order) {
+                Some(SelfTradePrevention::CancelOldest) => continue,
+                Some(_) => return None,
+                None if resting_order.all_or_none && resting_order.quantity > wanted - filled => continue,
+                None => filled += resting_order.quantity.min(wanted - filled),
+            }
+            if filled >= needed {
+                break;
+            }
+            if let Some(display_quantity) = resting_order.display_quantity.filter(|_| resting_order.hidden_quantity > 0) {
+                let peak = display_quantity.min(resting_order.hidden_quantity);
+                replenished.push_back(Order {
+                    quantity: peak,
+                    hidden_quantity: resting_order.hidden_quantity - peak,
+                    ..resting_order
+                });
+            }
+        }
+        Some(filled)
+    }
+
+    /// Returns how much of `wanted` the orders at one level would fill under the book's allocation
+    /// strategy, stopping once `needed` is reached. As in `sweep`, the level is allocated on what
+    /// each order displays and allocated again after every round of shares, with iceberg orders
+    /// showing their new peak at the back. Returns None if a self-trade would cancel part of the
+    /// incoming `order` first.
+    fn allocated_fill(&self, order: &Order, level: &PriceLevel, wanted: Quantity, needed: Quantity) -> Option<Quantity> {
+        let mut resting_orders: Vec<Order> = level.queue.iter(&self.orders).copied().collect();
+        let mut filled: Quantity = 0;
+        loop {
+            let allocations = self.allocation.allocate(wanted - filled, &resting_orders, self.rules.lot_size);
+            let mut shares: Vec<(usize, Quantity)> = allocations.into_iter()
+                .enumerate()
+                .filter(|(_, share)| *share > 0)
+                .collect();
+            if shares.is_empty() {
+                let front = resting_orders.iter()
+                    .position(|resting_order| !resting_order.all_or_none || resting_order.quantity <= wanted - filled);
+                match front {
+                    Some(index) => shares.push((index, resting_orders[index].quantity)),
+                    None => return Some(filled),
+                }
+            }
+
+            // filled and cancelled orders drop to zero and leave the level once the round is over
+            let mut replenished = Vec::new();
+            for (index, share) in shares {
+                let resting_order = &mut resting_orders[index];
+                match self.self_trade_mode(order, resting_order) {
+                    Some(SelfTradePrevention::CancelOldest) => {
+                        resting_order.quantity = 0;
+                        break;
                     }
-                    OrderType::FOK => {return false;}
-                    OrderType::IOC => {return false;}
-                    OrderType::Market => {
-                        if self.bid_tree.is_empty() {
-                            return false;
-                        } else {
-                            let bid_price = self.get_bid().unwrap().clone();
-                            let buy_order = self.bid_price_map.get_mut(&bid_price).unwrap().pop().unwrap();
-                            let remaining_order = self.match_order(self.buy_orders.get(&buy_order).unwrap().clone(),
-                                order,
-                                Side::Buy);
-                            if let Some(order) = remaining_order {
-                                self.add_order(order, false);
-                            }
-                        }
+                    Some(_) => return None,
+                    None => {}
+                }
+                let quantity = share.min(wanted - filled).min(resting_order.quantity);
+                filled += quantity;
+                if filled >= needed {
+                    return Some(filled);
+                }
+                resting_order.quantity -= quantity;
+                if let Some(display_quantity) = resting_order.display_quantity.filter(|_| resting_order.quantity == 0) {
+                    let peak = display_quantity.min(resting_order.hidden_quantity);
+                    if peak > 0 {
+                        replenished.push(Order {
+                            quantity: peak,
+                            hidden_quantity: resting_order.hidden_quantity - peak,
+                            ..*resting_order
+                        });
                     }
                 }
             }
+            resting_orders.retain(|resting_order| resting_order.quantity > 0);
+            resting_orders.extend(replenished);
         }
-        self.clean_empty_bid();
-        self.clean_empty_ask();
-        return true;
     }
 
-    fn match_order(&mut self, buy_order: Order, sell_order: Order, price_side: Side) -> Option<Order> {
-        let quantity = std::cmp::min(buy_order.quantity, sell_order.quantity);
-        let price: Price;
-        match price_side {
-            Side::Buy => {
-                price = buy_order.price;
-            }
-            Side::Sell => {
-                price = sell_order.price;
+    /// Returns the book's self-trade prevention mode if matching `order` against `resting_order`
+    /// would be a self-trade.
+    fn self_trade_mode(&self, order: &Order, resting_order: &Order) -> Option<SelfTradePrevention> {
+        match (order.owner, resting_order.owner) {
+            (Some(owner), Some(resting_owner)) if owner == resting_owner => self.self_trade_prevention,
+            _ => None,
+        }
+    }
+
+    /// Applies the self-trade prevention `mode` to the resting side of a prevented match and
+    /// returns how much of each order it cancels. The caller takes its share off the incoming order.
+    fn prevent_self_trade(&mut self, mode: SelfTradePrevention, order: &Order, resting_order: &Order) -> SelfTradePrevented {
+        let (incoming_cancelled, resting_cancelled) = match mode {
+            SelfTradePrevention::CancelNewest => (order.quantity, 0),
+            SelfTradePrevention::CancelOldest => (0, resting_order.open_quantity()),
+            SelfTradePrevention::CancelBoth => (order.quantity, resting_order.open_quantity()),
+            SelfTradePrevention::DecrementAndCancel => {
+                let quantity = std::cmp::min(order.quantity, resting_order.open_quantity());
+                (quantity, quantity)
             }
+        };
+        if resting_cancelled > 0 {
+            self.decrease_order(resting_order.id, resting_cancelled, CancelReason::SelfTradePrevention);
+        }
+        SelfTradePrevented {
+            resting_order: resting_order.id,
+            mode,
+            incoming_cancelled,
+            resting_cancelled,
+        }
+    }
+
+    /// Returns the best price on the side of the book an order on `side` would match against.
+    fn best_opposite(&self, side: Side) -> Option<Price> {
+        match side {
+            Side::Buy => self.get_ask().copied(),
+            Side::Sell => self.get_bid().copied(),
         }
+    }
+
+    /// Trades `quantity` between two orders at the price of the order on `price_side`, and takes it
+    /// off whichever of them rests on the book.
+    fn match_order(&mut self, buy_order: Order, sell_order: Order, price_side: Side, quantity: Quantity) {
+        let price = match price_side {
+            Side::Buy => buy_order.price,
+            Side::Sell => sell_order.price,
+        };
         let trade: Trade = Trade {
-            buy_order: buy_order.clone(),
-            sell_order: sell_order.clone(),
+            id: self.trades.next_id(),
+            sequence: self.next_sequence(),
+            timestamp: self.clock.now(),
+            buy_order,
+            sell_order,
             price,
             quantity,
         };
-        self.trades.insert((buy_order.id, sell_order.id), trade);
+        let trade_id = trade.id;
+        self.trades.push(trade);
+        self.emit(BookEvent::Trade {
+            trade_id,
+            price,
+            quantity,
+            buy_order_id: buy_order.id,
+            sell_order_id: sell_order.id,
+            aggressor: price_side.opposite(),
+        });
         self.buy_volume -= quantity;
         self.sell_volume -= quantity;
 
-        if buy_order.quantity == sell_order.quantity {
-            self.buy_orders.remove(&buy_order.id);
-            self.sell_orders.remove(&sell_order.id);
-            Option::None
-        } else if buy_order.quantity > sell_order.quantity {
-            let remaining_quantity = buy_order.quantity - quantity;
-            let remaining_order = Order {
-                id: buy_order.id,
-                kind: buy_order.kind,
-                quantity: remaining_quantity,
-                price: buy_order.price,
-                side: buy_order.side,
-            };
-            Option::Some(remaining_order)
-        } else {
-            let remaining_quantity = sell_order.quantity - quantity;
-            let remaining_order = Order {
-                id: sell_order.id,
-                kind: sell_order.kind,
-                quantity: remaining_quantity,
-                price: sell_order.price,
-                side: sell_order.side,
-            };
-            Option::Some(remaining_order)
+        // resting orders keep their place in the queue until they are fully filled
+        for order in [buy_order, sell_order] {
+            if self.order_handles.contains_key(&order.id) {
+                self.emit(BookEvent::OrderExecuted {
+                    order_id: order.id,
+                    side: order.side,
+                    price: order.price,
+                    quantity,
+                });
+                self.reduce_order(order.id, quantity);
+            }
         }
     }
 
-    // private function to add a GTC order to the heap, place_order method is the public API
-    fn add_order(&mut self, order: Order, test: bool) {
+    /// Takes `quantity` off the displayed quantity of a resting order and its price level.
+    /// An iceberg order whose peak reaches zero shows a new peak from its reserve and goes to the
+    /// back of its queue. Any other order that reaches zero leaves its queue, and a level that
+    /// empties is removed from the book straight away.
+    /// Orders that are not resting on the book, such as an incoming order being matched, are ignored.
+    fn reduce_order(&mut self, id: OrderId, quantity: Quantity) {
+        let handle = match self.order_handles.get(&id) {
+            Some(handle) => *handle,
+            None => return,
+        };
+        let order = self.orders.get_mut(handle);
+        order.quantity -= quantity;
+        let (side, price, filled) = (order.side, order.price, order.quantity == 0);
+        let replenished = match order.display_quantity {
+            Some(display_quantity) if filled && order.hidden_quantity > 0 => {
+                order.quantity = display_quantity.min(order.hidden_quantity);
+                order.hidden_quantity -= order.quantity;
+                order.quantity
+            }
+            _ => 0,
+        };
+
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.get_mut(&price).unwrap();
+        level.quantity -= quantity;
+        if replenished > 0 {
+            level.quantity += replenished;
+            level.hidden_quantity -= replenished;
+            level.queue.remove(&mut self.orders, handle);
+            level.queue.push(&mut self.orders, handle);
+            match side {
+                Side::Buy => {
+                    self.buy_volume += replenished;
+                    self.buy_hidden_volume -= replenished;
+                }
+                Side::Sell => {
+                    self.sell_volume += replenished;
+                    self.sell_hidden_volume -= replenished;
+                }
+            }
+        } else if filled {
+            level.queue.remove(&mut self.orders, handle);
+            let order = self.orders.remove(handle);
+            self.order_handles.remove(&id);
+            if let Some(expires_at) = order.expires_at.filter(|_| order.kind == OrderType::GTD) {
+                self.expiries.remove(&(expires_at, id));
+            }
+        }
+        let (level_quantity, order_count) = (level.quantity, level.len());
+        if level.is_empty() {
+            levels.remove(&price);
+        }
+
+        if replenished > 0 {
+            self.emit(BookEvent::OrderAdded {
+                order_id: id,
+                side,
+                price,
+                quantity: replenished,
+            });
+        }
+        self.emit(BookEvent::LevelChanged {
+            side,
+            price,
+            quantity: level_quantity,
+            order_count,
+        });
+    }
+
+    // private function to add a GTC order to its price level, place_order method is the public API
+    fn add_order(&mut self, mut order: Order) {
+        order.min_execution_quantity = None; // it only applies while the order is arriving
+        if let Some(display_quantity) = order.display_quantity {
+            let open_quantity = order.open_quantity();
+            order.quantity = open_quantity.min(display_quantity);
+            order.hidden_quantity = open_quantity - order.quantity;
+        }
+        let handle = self.orders.insert(order);
+        self.order_handles.insert(order.id, handle);
+        if let Some(expires_at) = order.expires_at.filter(|_| order.kind == OrderType::GTD) {
+            self.expiries.insert((expires_at, order.id));
+        }
+        let levels = match order.side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.entry(order.price).or_insert_with(PriceLevel::new);
+        level.push(&mut self.orders, handle);
+        let (level_quantity, order_count) = (level.quantity, level.len());
+        self.emit(BookEvent::OrderAdded {
+            order_id: order.id,
+            side: order.side,
+            price: order.price,
+            quantity: order.quantity,
+        });
+        self.emit(BookEvent::LevelChanged {
+            side: order.side,
+            price: order.price,
+            quantity: level_quantity,
+            order_count,
+        });
         match order.side {
             Side::Buy => {
-                self.buy_orders.insert(order.id, order);
-                self.bid_tree.push(order.price);
-                self.bid_price_map.entry(order.price).or_insert(OrderQueue::new()).push(order.id);
-                if test {
-                    self.buy_volume += order.quantity; // volume is adjusted in place_order, but to test other functions we need to adjust it here
-                }
+                self.buy_volume += order.quantity;
+                self.buy_hidden_volume += order.hidden_quantity;
             }
             Side::Sell => {
-                self.sell_orders.insert(order.id, order);
-                self.ask_tree.push(order.price);
-                self.ask_price_map.entry(order.price).or_insert(OrderQueue::new()).push(order.id);
-                if test {
-                    self.sell_volume += order.quantity;
-                }
+                self.sell_volume += order.quantity;
+                self.sell_hidden_volume += order.hidden_quantity;
             }
         }
     }
 
-    pub fn cancel_order(&mut self, id: i32) -> bool {
-        if let Some(order) = self.buy_orders.get(&id).or(self.sell_orders.get(&id)) {
-            match order.side {
-                Side::Buy => {
-                    if let Some(order) = self.buy_orders.remove(&id) {
-                        self.bid_price_map.get_mut(&order.price).unwrap().remove_order(id);
-                        self.buy_volume -= order.quantity;
-                        true
-                    } else {
-                        false
-                    }
-                }
-                Side::Sell => {
-                    if let Some(order) = self.sell_orders.remove(&id) {
-                        self.ask_price_map.get_mut(&order.price).unwrap().remove_order(id);
-                        self.sell_volume -= order.quantity;
-                        true
-                    } else {
-                    false
-                    }
-                }
+    /// Removes a resting order, or a stop order waiting to be triggered, from the book and
+    /// returns it with its unfilled quantity.
+    pub fn cancel_order(&mut self, id: OrderId) -> Result<Order, OrderBookError> {
+        if let Some(order) = self.stops.remove(id) {
+            self.next_sequence();
+            return Ok(order);
+        }
+        let order = match self.order_handles.get(&id) {
+            Some(handle) => *self.orders.get(*handle),
+            None => return Err(OrderBookError::UnknownOrderId(id)),
+        };
+        self.decrease_order(id, order.open_quantity(), CancelReason::Cancelled);
+        Ok(order)
+    }
+
+    /// Takes `quantity` off a resting order without trading it, as a cancel or an amend does.
+    /// The hidden reserve of an iceberg order goes before its displayed quantity.
+    fn decrease_order(&mut self, id: OrderId, quantity: Quantity, reason: CancelReason) {
+        let order = self.orders.get_mut(self.order_handles[&id]);
+        let hidden = quantity.min(order.hidden_quantity);
+        order.hidden_quantity -= hidden;
+        let (side, price) = (order.side, order.price);
+        self.next_sequence();
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        levels.get_mut(&price).unwrap().hidden_quantity -= hidden;
+        if quantity > hidden {
+            self.emit(BookEvent::OrderCancelled {
+                order_id: id,
+                side,
+                price,
+                quantity: quantity - hidden,
+                reason,
+            });
+            self.reduce_order(id, quantity - hidden);
+        }
+        match side {
+            Side::Buy => {
+                self.buy_volume -= quantity - hidden;
+                self.buy_hidden_volume -= hidden;
             }
-        } else {
-            return false;
+            Side::Sell => {
+                self.sell_volume -= quantity - hidden;
+                self.sell_hidden_volume -= hidden;
+            }
+        }
+    }
+
+    /// Removes every resting GTD order whose expiry time has been reached on the book's clock,
+    /// earliest expiry first. Placing or amending an order does this before matching, so an
+    /// expired order never trades.
+    pub fn expire_orders(&mut self) -> Vec<OrderExpired> {
+        let now = self.clock.now();
+        self.expire_orders_at(now)
+    }
+
+    /// Removes every resting GTD order that has expired by `now`, earliest expiry first.
+    fn expire_orders_at(&mut self, now: Timestamp) -> Vec<OrderExpired> {
+        let mut expired = Vec::new();
+        while let Some(&(expires_at, id)) = self.expiries.first() {
+            if expires_at > now {
+                break;
+            }
+            expired.push(self.expire_order(id, now));
+        }
+        expired
+    }
+
+    /// Ends the trading session: every resting DAY order expires, in order id order, after any
+    /// GTD order whose expiry time has been reached.
+    pub fn end_session(&mut self) -> Vec<OrderExpired> {
+        let mut expired = self.expire_orders();
+        let now = self.clock.now();
+        let mut day_orders: Vec<OrderId> = self.order_handles.iter()
+            .filter(|(_, handle)| self.orders.get(**handle).kind == OrderType::Day)
+            .map(|(id, _)| *id)
+            .collect();
+        day_orders.sort_unstable();
+        for id in day_orders {
+            expired.push(self.expire_order(id, now));
+        }
+        expired
+    }
+
+    fn expire_order(&mut self, id: OrderId, now: Timestamp) -> OrderExpired {
+        let quantity = self.orders.get(self.order_handles[&id]).open_quantity();
+        self.decrease_order(id, quantity, CancelReason::Expired);
+        OrderExpired {
+            order_id: id,
+            quantity,
+            timestamp: now,
         }
     }
 
+    /// Sequence number of the latest change to the book. Every accepted order, amend, cancel and trade
+    /// takes the next number, so the value only moves forward.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    fn emit(&mut self, event: BookEvent) {
+        if self.record_events {
+            self.event_sequence += 1;
+            self.events.push(SequencedEvent {
+                sequence: self.event_sequence,
+                event,
+            });
+        }
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    /// Price of the most recent trade on the book.
+    pub fn last_trade_price(&self) -> Option<Price> {
+        self.trades.last().map(|trade| trade.price)
+    }
+
+    /// Returns the price a waiting stop order triggers at. For a trailing stop this is the current
+    /// level of its trail.
+    pub fn trigger_price(&self, id: OrderId) -> Option<Price> {
+        self.stops.stop_price(id)
+    }
+
+    /// Returns a stop order that is waiting off the book for its stop price.
+    pub fn pending_stop(&self, id: OrderId) -> Option<&Order> {
+        self.stops.get(id)
+    }
+
+    /// Iterates over the stop orders waiting off the book, buys first, each by stop price.
+    pub fn pending_stops(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.stops.iter()
+    }
+
     pub fn get_bid(&self) -> Option<&Price> {
-        self.bid_tree.peek()
+        self.bids.keys().next_back()
     }
 
     pub fn get_ask(&self) -> Option<&Price> {
-        self.ask_tree.peek()
+        self.asks.keys().next()
     }
 
-    fn clean_empty_bid(&mut self) {
-        while let Some(price) = self.get_bid() {
-            if let Some(price) = self.bid_price_map.get(price) {
-                if price.is_empty() {
-                    self.bid_tree.pop();
-                } else {
-                    break;
-                }
-            } else {
-                break;
-            }
+    /// Iterates over the bid levels from the highest price down, yielding each price with
+    /// the total displayed quantity resting at it.
+    pub fn bid_levels(&self) -> impl Iterator<Item = (Price, Quantity)> + '_ {
+        self.bids.iter().rev().map(|(price, level)| (*price, level.quantity))
+    }
+
+    /// Iterates over the ask levels from the lowest price up, yielding each price with
+    /// the total displayed quantity resting at it.
+    pub fn ask_levels(&self) -> impl Iterator<Item = (Price, Quantity)> + '_ {
+        self.asks.iter().map(|(price, level)| (*price, level.quantity))
+    }
+
+    /// Returns the best `levels` price levels of each side with their aggregated quantity and
+    /// number of orders.
+    pub fn depth(&self, levels: usize) -> Depth {
+        Depth {
+            bids: self.bid_depth().take(levels).collect(),
+            asks: self.ask_depth().take(levels).collect(),
         }
     }
 
-    fn clean_empty_ask(&mut self) {
-        while let Some(price) = self.get_ask() {
-            if let Some(price) = self.ask_price_map.get(price) {
-                if price.is_empty() {
-                    self.ask_tree.pop();
-                } else {
-                    break;
-                }
-            } else {
-                break;
-            }
+    /// Iterates over every bid level from the highest price down.
+    pub fn bid_depth(&self) -> impl Iterator<Item = DepthLevel> + '_ {
+        self.levels_from_best(Side::Buy).map(|(price, level)| depth_level(*price, level))
+    }
+
+    /// Iterates over every ask level from the lowest price up.
+    pub fn ask_depth(&self) -> impl Iterator<Item = DepthLevel> + '_ {
+        self.levels_from_best(Side::Sell).map(|(price, level)| depth_level(*price, level))
+    }
+
+    /// Returns a resting order with its position in the queue of its price level.
+    pub fn get_order(&self, id: OrderId) -> Option<RestingOrder> {
+        let order = *self.orders.get(*self.order_handles.get(&id)?);
+        let (mut queue_position, mut quantity_ahead) = (0, 0);
+        for ahead in self.orders_at(order.side, order.price).take_while(|ahead| ahead.id != id) {
+            queue_position += 1;
+            quantity_ahead += ahead.quantity;
+        }
+        Some(RestingOrder {
+            order,
+            queue_position,
+            quantity_ahead,
+        })
+    }
+
+    /// Iterates over the orders resting at one price on one side of the book, in time priority.
+    pub fn orders_at(&self, side: Side, price: Price) -> impl Iterator<Item = &Order> + '_ {
+        self.levels(side).get(&price).into_iter().flat_map(|level| level.queue.iter(&self.orders))
+    }
+
+    /// Iterates over every resting bid, best price first and in time priority within a price.
+    pub fn bid_orders(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.levels_from_best(Side::Buy).flat_map(|(_, level)| level.queue.iter(&self.orders))
+    }
+
+    /// Iterates over every resting ask, best price first and in time priority within a price.
+    pub fn ask_orders(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.levels_from_best(Side::Sell).flat_map(|(_, level)| level.queue.iter(&self.orders))
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<Price, PriceLevel> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    /// Iterates over the levels of one side of the book, best price first.
+    fn levels_from_best(&self, side: Side) -> Box<dyn Iterator<Item = (&Price, &PriceLevel)> + '_> {
+        match side {
+            Side::Buy => Box::new(self.bids.iter().rev()),
+            Side::Sell => Box::new(self.asks.iter()),
         }
     }
 }
 
+fn depth_level(price: Price, level: &PriceLevel) -> DepthLevel {
+    DepthLevel {
+        price,
+        quantity: level.quantity,
+        hidden_quantity: level.hidden_quantity,
+        order_count: level.len(),
+    }
+}
+
+/// Turns a stop order triggered at `stop_price` into the order it places: a market order for a
+/// stop market order, a GTC limit order at the order's price for a stop limit order, and a GTC
+/// limit order priced off the stop price for a trailing stop limit order.
+fn activate_stop(order: Order, stop_price: Price) -> Order {
+    match order.kind {
+        OrderType::StopLimit => Order { kind: OrderType::GTC, ..order },
+        OrderType::TrailingStopLimit => Order {
+            kind: OrderType::GTC,
+            price: order.trail.unwrap().limit_price(order.side, stop_price),
+            ..order
+        },
+        _ => Order { kind: OrderType::Market, ..order },
+    }
+}
+
+/// Returns true if `order` is a GTD order whose expiry time has been reached by `now`.
+fn has_expired(order: &Order, now: Timestamp) -> bool {
+    order.kind == OrderType::GTD && order.expires_at.is_some_and(|expires_at| expires_at <= now)
+}
+
+/// Returns true if an order on `side` limited to `limit` may trade against a resting order at `price`.
+/// A `limit` of `None` crosses every price.
+fn crosses(side: Side, price: Price, limit: Option<Price>) -> bool {
+    match (side, limit) {
+        (_, None) => true,
+        (Side::Buy, Some(limit)) => price <= limit,
+        (Side::Sell, Some(limit)) => price >= limit,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::report::OrderStatus;
     use crate::types::{Price, Quantity, OrderId, 
                        Side, OrderType};
 
+    impl OrderBook {
+        fn front_order_id(&self, levels: &BTreeMap<Price, PriceLevel>, price: Price) -> Option<OrderId> {
+            let handle = levels.get(&price)?.queue.peek()?;
+            Some(self.orders.get(handle).id)
+        }
+    }
+
     #[test]
     fn add_buy_order_same_price() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Buy);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(100.0), Side::Buy);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Buy);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
 
-        assert_eq!(orderbook.bid_price_map.get(&Price(100.0)).unwrap().0.len(), 2);
-        assert_eq!(orderbook.bid_price_map.get(&Price(100.0)).unwrap().peek().unwrap(), &1);
-        assert_eq!(orderbook.bid_price_map.len(), 1);
+        assert_eq!(orderbook.bids.get(&Price::from_int(100)).unwrap().len(), 2);
+        assert_eq!(orderbook.front_order_id(&orderbook.bids, Price::from_int(100)), Some(1));
+        assert_eq!(orderbook.bids.len(), 1);
     }
 
     #[test]
     fn add_buy_order_diff_price() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Buy);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(120.0), Side::Buy);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(120), Side::Buy);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
 
-        assert_eq!(orderbook.bid_price_map.get(&Price(100.0)).unwrap().0.len(), 1);
-        assert_eq!(orderbook.bid_price_map.get(&Price(100.0)).unwrap().peek().unwrap(), &1);
-        assert_eq!(orderbook.bid_price_map.len(), 2);
+        assert_eq!(orderbook.bids.get(&Price::from_int(100)).unwrap().len(), 1);
+        assert_eq!(orderbook.front_order_id(&orderbook.bids, Price::from_int(100)), Some(1));
+        assert_eq!(orderbook.bids.len(), 2);
     }
 
     #[test]
     fn add_sell_order_same_price() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Sell);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(100.0), Side::Sell);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Sell);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Sell);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
 
-        assert_eq!(orderbook.ask_price_map.get(&Price(100.0)).unwrap().0.len(), 2);
-        assert_eq!(orderbook.ask_price_map.get(&Price(100.0)).unwrap().peek().unwrap(), &1);
-        assert_eq!(orderbook.ask_price_map.len(), 1);
+        assert_eq!(orderbook.asks.get(&Price::from_int(100)).unwrap().len(), 2);
+        assert_eq!(orderbook.front_order_id(&orderbook.asks, Price::from_int(100)), Some(1));
+        assert_eq!(orderbook.asks.len(), 1);
     }
 
     #[test]
     fn add_sell_order_diff_price() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Sell);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(120.0), Side::Sell);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Sell);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(120), Side::Sell);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
 
-        assert_eq!(orderbook.ask_price_map.get(&Price(100.0)).unwrap().0.len(), 1);
-        assert_eq!(orderbook.ask_price_map.get(&Price(100.0)).unwrap().peek().unwrap(), &1);
-        assert_eq!(orderbook.ask_price_map.len(), 2);
+        assert_eq!(orderbook.asks.get(&Price::from_int(100)).unwrap().len(), 1);
+        assert_eq!(orderbook.front_order_id(&orderbook.asks, Price::from_int(100)), Some(1));
+        assert_eq!(orderbook.asks.len(), 2);
     }
 
     #[test]
     fn cancel_buy_order() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Buy);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(100.0), Side::Buy);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Buy);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
 
-        orderbook.cancel_order(1);
+        orderbook.cancel_order(1).unwrap();
 
-        assert_eq!(orderbook.bid_price_map.get(&Price(100.0)).unwrap().0.len(), 1);
-        assert_eq!(orderbook.bid_price_map.get(&Price(100.0)).unwrap().peek().unwrap(), &2);
-        assert_eq!(orderbook.bid_price_map.len(), 1);
+        assert_eq!(orderbook.bids.get(&Price::from_int(100)).unwrap().len(), 1);
+        assert_eq!(orderbook.front_order_id(&orderbook.bids, Price::from_int(100)), Some(2));
+        assert_eq!(orderbook.bids.len(), 1);
     }
 
     #[test]
     fn cancel_sell_order() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Sell);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(100.0), Side::Sell);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Sell);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Sell);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
+
+        orderbook.cancel_order(1).unwrap();
+
+        assert_eq!(orderbook.asks.get(&Price::from_int(100)).unwrap().len(), 1);
+        assert_eq!(orderbook.front_order_id(&orderbook.asks, Price::from_int(100)), Some(2));
+        assert_eq!(orderbook.asks.len(), 1);
+    }
 
-        orderbook.cancel_order(1);
+    #[test]
+    fn level_removed_when_emptied() {
+        let mut orderbook = OrderBook::new();
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(120), Side::Buy);
+        let order3 = Order::new(3, OrderType::GTC, 200, Price::from_int(90), Side::Buy);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
+        orderbook.add_order(order3);
+        assert_eq!(orderbook.bids.len(), 3);
+        assert_eq!(orderbook.bids.get(&Price::from_int(120)).unwrap().quantity, 150);
 
-        assert_eq!(orderbook.ask_price_map.get(&Price(100.0)).unwrap().0.len(), 1);
-        assert_eq!(orderbook.ask_price_map.get(&Price(100.0)).unwrap().peek().unwrap(), &2);
-        assert_eq!(orderbook.ask_price_map.len(), 1);
+        orderbook.cancel_order(1).unwrap();
+        assert_eq!(orderbook.bids.len(), 2);
+        orderbook.cancel_order(2).unwrap();
+        assert_eq!(orderbook.bids.len(), 1);
+        assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(90));
     }
 
     #[test]
-    fn clean_empty_bid() {
+    fn level_quantity_tracks_fills() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Buy);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(120.0), Side::Buy);
-        let order3 = Order::new(3, OrderType::GTC, 200, Price(90.0), Side::Buy);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
-        orderbook.add_order(order3, true);
-
-        orderbook.cancel_order(1);
-
-        assert_eq!(orderbook.bid_tree.len(), 3);
-        orderbook.clean_empty_bid();
-        assert_eq!(orderbook.bid_tree.len(), 3);
-        let _ = orderbook.bid_tree.pop();
-        orderbook.clean_empty_bid();
-        assert_eq!(orderbook.bid_tree.len(), 1);
+        orderbook.add_order(Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Sell));
+        orderbook.add_order(Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Sell));
+        assert_eq!(orderbook.asks.get(&Price::from_int(100)).unwrap().quantity, 250);
+
+        orderbook.place_order(Order::new(3, OrderType::GTC, 120, Price::from_int(100), Side::Buy)).unwrap();
+        let level = orderbook.asks.get(&Price::from_int(100)).unwrap();
+        assert_eq!(level.quantity, 130);
+        assert_eq!(level.len(), 1);
+        assert_eq!(orderbook.orders.get(level.queue.peek().unwrap()).id, 2);
     }
+
     #[test]
     fn match_order_test() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(100.0), Side::Buy);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(100.0), Side::Sell);
-        orderbook.add_order(order1, true);
-        orderbook.add_order(order2, true);
-        orderbook.match_order(order1, order2, Side::Buy);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Sell);
+        orderbook.add_order(order1);
+        orderbook.add_order(order2);
+        orderbook.match_order(order1, order2, Side::Buy, 100);
         assert_eq!(orderbook.trades.len(), 1);
-        assert_eq!(orderbook.trades.get(&(1, 2)).unwrap().quantity, 100);
+        assert_eq!(orderbook.trades.between(1, 2).next().unwrap().quantity, 100);
         assert_eq!(orderbook.buy_volume, 0);
         assert_eq!(orderbook.sell_volume, 50);
-        assert_eq!(orderbook.bid_tree.len(), 1); // we have not cleaned the empty bid
-        assert_eq!(orderbook.ask_tree.len(), 1);
+        assert_eq!(orderbook.bids.len(), 0); // the filled bid's level is removed straight away
+        assert_eq!(orderbook.asks.len(), 1);
 
-        let order3 = Order::new(3, OrderType::GTC, 50, Price(110.0), Side::Buy);
-        orderbook.add_order(order3, true);
-        orderbook.match_order(order3, order2, Side::Sell);
+        let order3 = Order::new(3, OrderType::GTC, 50, Price::from_int(110), Side::Buy);
+        orderbook.add_order(order3);
+        orderbook.match_order(order3, order2, Side::Sell, 50);
         assert_eq!(orderbook.trades.len(), 2);
-        assert_eq!(orderbook.trades.get(&(3, 2)).unwrap().quantity, 50);
-        assert_eq!(orderbook.trades.get(&(3, 2)).unwrap().price, Price(100.0));
+        assert_eq!(orderbook.trades.between(3, 2).next().unwrap().quantity, 50);
+        assert_eq!(orderbook.trades.between(3, 2).next().unwrap().price, Price::from_int(100));
         assert_eq!(orderbook.buy_volume, 0);
         assert_eq!(orderbook.sell_volume, 0);
-        assert_eq!(orderbook.bid_tree.len(), 2); // we have not cleaned the empty bids
-        assert_eq!(orderbook.ask_tree.len(), 1); // we have not cleaned the empty ask
+        assert_eq!(orderbook.bids.len(), 0);
+        assert_eq!(orderbook.asks.len(), 0);
     }
 
     #[test]
     fn place_gtc_order_and_match() {
         let mut orderbook = OrderBook::new();
-        let order1 = Order::new(1, OrderType::GTC, 100, Price(110.0), Side::Buy);
-        let order2 = Order::new(2, OrderType::GTC, 150, Price(100.0), Side::Sell);
+        let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(110), Side::Buy);
+        let order2 = Order::new(2, OrderType::GTC, 150, Price::from_int(100), Side::Sell);
         
-        let result = orderbook.place_order(order1);
-        assert_eq!(result, true);
-        let result = orderbook.place_order(order2);
-        assert_eq!(result, true);
+        let report = orderbook.place_order(order1).unwrap();
+        assert_eq!(report.status, OrderStatus::Rested);
+        let report = orderbook.place_order(order2).unwrap();
+        assert_eq!(report.status, OrderStatus::PartiallyFilled);
+        assert_eq!(report.fills, vec![Fill { counterparty: 1, price: Price::from_int(110), quantity: 100 }]);
+        assert!(report.self_trade_prevented.is_empty());
+        assert_eq!(report.remaining_quantity, 50);
 
         assert_eq!(orderbook.trades.len(), 1);
-        assert_eq!(orderbook.trades.get(&(1, 2)).unwrap().quantity, 100);
-        assert_eq!(orderbook.trades.get(&(1 ,2)).unwrap().price, Price(110.0));
+        assert_eq!(orderbook.trades.between(1, 2).next().unwrap().quantity, 100);
+        assert_eq!(orderbook.trades.between(1, 2).next().unwrap().price, Price::from_int(110));
         assert_eq!(orderbook.buy_volume, 0);
         assert_eq!(orderbook.sell_volume, 50); 
-        assert_eq!(orderbook.bid_tree.len(), 0);
-        assert_eq!(orderbook.ask_tree.len(), 1);
+        assert_eq!(orderbook.bids.len(), 0);
+        assert_eq!(orderbook.asks.len(), 1);
     }
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use crate::types::{Price, Quantity, OrderId, SelfTradePrevention, Timestamp};
+use crate::order::Order;
+
+/// Final state of an order once `OrderBook::place_order` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Pending,         // a stop order waiting off the book for its stop price
+    Rested,          // nothing matched, the whole order rests on the book
+    PartiallyFilled, // some quantity matched, the remainder rests on the book
+    Filled,          // the whole order matched
+    Cancelled,       // nothing is left open and part of the order was discarded unfilled
+    Rejected,        // the order was refused and had no effect on the book
+}
+
+/// Why an order was rejected by the matching logic. Orders that fail validation are refused
+/// with an `OrderBookError` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// A fill-or-kill or all-or-none order could not be filled completely, or an order could not
+    /// fill its minimum execution quantity.
+    InsufficientLiquidity,
+    WouldTakeLiquidity,    // a post-only order would have matched on arrival
+}
+
+/// A single execution of an incoming order against a resting order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fill {
+    pub counterparty: OrderId,
+    pub price: Price,
+    pub quantity: Quantity,
+}
+
+/// A match between two orders of the same owner that the book's self-trade prevention mode stopped.
+/// The incoming order is the one the report belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTradePrevented {
+    pub resting_order: OrderId,
+    pub mode: SelfTradePrevention,
+    pub incoming_cancelled: Quantity, // quantity taken off the incoming order
+    pub resting_cancelled: Quantity,  // quantity taken off the resting order
+}
+
+/// A resting order the book removed because its time in force ran out. Expiries are reported
+/// apart from cancels, which only ever come from the owner of the order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderExpired {
+    pub order_id: OrderId,
+    pub quantity: Quantity, // open quantity removed from the book
+    pub timestamp: Timestamp,
+}
+
+/// ExecutionReport describes everything that happened to an order placed on the book.
+/// `filled_quantity`, `remaining_quantity` and `cancelled_quantity` always add up to the
+/// quantity the order was placed with, unless it was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionReport {
+    pub order_id: OrderId,
+    pub status: OrderStatus,
+    pub fills: Vec<Fill>,
+    pub filled_quantity: Quantity,
+    pub remaining_quantity: Quantity, // quantity left open on the book
+    pub cancelled_quantity: Quantity,
+    pub reject_reason: Option<RejectReason>,
+    pub self_trade_prevented: Vec<SelfTradePrevented>, // reported apart from fills, nothing traded
+    pub triggered: Vec<ExecutionReport>, // stop orders released by this order's trades, in execution order
+    pub repriced: Option<Price>, // the price a sliding post-only order was moved to
+    pub expired: Vec<OrderExpired>, // resting orders that expired before this order was matched
+}
+
+impl ExecutionReport {
+    /// Starts the report of an order that is about to be matched.
+    pub(crate) fn new(order: &Order) -> ExecutionReport {
+        ExecutionReport {
+            order_id: order.id,
+            status: OrderStatus::Rested,
+            fills: Vec::new(),
+            filled_quantity: 0,
+            remaining_quantity: 0,
+            cancelled_quantity: 0,
+            reject_reason: None,
+            self_trade_prevented: Vec::new(),
+            triggered: Vec::new(),
+            repriced: None,
+            expired: Vec::new(),
+        }
+    }
+
+    pub(crate) fn fill(&mut self, fill: Fill) {
+        self.filled_quantity += fill.quantity;
+        self.fills.push(fill);
+    }
+
+    pub(crate) fn cancel(&mut self, quantity: Quantity) {
+        self.cancelled_quantity += quantity;
+    }
+
+    pub(crate) fn prevent_self_trade(&mut self, prevented: SelfTradePrevented) {
+        self.cancel(prevented.incoming_cancelled);
+        self.self_trade_prevented.push(prevented);
+    }
+
+    /// Completes the report once matching is over and `remaining_quantity` is left resting on the book.
+    pub(crate) fn finish(mut self, remaining_quantity: Quantity) -> ExecutionReport {
+        self.remaining_quantity = remaining_quantity;
+        self.status = if remaining_quantity > 0 && self.filled_quantity == 0 {
+            OrderStatus::Rested
+        } else if remaining_quantity > 0 {
+            OrderStatus::PartiallyFilled
+        } else if self.cancelled_quantity > 0 {
+            OrderStatus::Cancelled
+        } else {
+            OrderStatus::Filled
+        };
+        self
+    }
+
+    pub(crate) fn pending(order: &Order) -> ExecutionReport {
+        ExecutionReport {
+            status: OrderStatus::Pending,
+            remaining_quantity: order.quantity,
+            ..ExecutionReport::new(order)
+        }
+    }
+
+    pub(crate) fn rejected(order: &Order, reason: RejectReason) -> ExecutionReport {
+        ExecutionReport {
+            status: OrderStatus::Rejected,
+            reject_reason: Some(reason),
+            ..ExecutionReport::new(order)
+        }
+    }
+}
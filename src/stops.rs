@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::order::Order;
+use crate::types::{Price, OrderId, Side};
+
+/// StopStore holds stop orders off the book until the price they wait for trades.
+/// Buy stops trigger once the market trades at or above their stop price, sell stops once it
+/// trades at or below it. Each stop keeps the book sequence number it arrived with, which decides
+/// the order in which stops that trigger together are released.
+/// The stop price of a trailing stop moves with the market, see `ratchet`.
+#[derive(Debug, Default)]
+pub(crate) struct StopStore {
+    buys: BTreeMap<(Price, u64), Order>,  // (stop price, arrival sequence) -> order
+    sells: BTreeMap<(Price, u64), Order>,
+    index: HashMap<OrderId, (Side, Price, u64)>,
+    trailing: BTreeSet<OrderId>,
+}
+
+impl StopStore {
+
+    pub(crate) fn new() -> StopStore {
+        StopStore {
+            buys: BTreeMap::new(),
+            sells: BTreeMap::new(),
+            index: HashMap::new(),
+            trailing: BTreeSet::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, order: Order, stop_price: Price, sequence: u64) {
+        self.index.insert(order.id, (order.side, stop_price, sequence));
+        if order.kind.is_trailing() {
+            self.trailing.insert(order.id);
+        }
+        match order.side {
+            Side::Buy => self.buys.insert((stop_price, sequence), order),
+            Side::Sell => self.sells.insert((stop_price, sequence), order),
+        };
+    }
+
+    pub(crate) fn remove(&mut self, id: OrderId) -> Option<Order> {
+        let (side, stop_price, sequence) = self.index.remove(&id)?;
+        self.trailing.remove(&id);
+        match side {
+            Side::Buy => self.buys.remove(&(stop_price, sequence)),
+            Side::Sell => self.sells.remove(&(stop_price, sequence)),
+        }
+    }
+
+    pub(crate) fn contains(&self, id: OrderId) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    pub(crate) fn get(&self, id: OrderId) -> Option<&Order> {
+        let (side, stop_price, sequence) = self.index.get(&id)?;
+        match side {
+            Side::Buy => self.buys.get(&(*stop_price, *sequence)),
+            Side::Sell => self.sells.get(&(*stop_price, *sequence)),
+        }
+    }
+
+    /// Returns the price a waiting stop triggers at.
+    pub(crate) fn stop_price(&self, id: OrderId) -> Option<Price> {
+        self.index.get(&id).map(|(_, stop_price, _)| *stop_price)
+    }
+
+    /// Iterates over the waiting buy stops and then the waiting sell stops, each by stop price and
+    /// then arrival.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.buys.values().chain(self.sells.values())
+    }
+
+    /// Returns the waiting trailing stop orders.
+    pub(crate) fn trailing(&self) -> Vec<Order> {
+        self.trailing.iter().map(|id| *self.get(*id).unwrap()).collect()
+    }
+
+    /// Moves the stop price of a trailing stop to `stop_price` if that is in the order's favour:
+    /// higher for a sell stop, lower for a buy stop. The stop keeps its arrival sequence.
+    pub(crate) fn ratchet(&mut self, id: OrderId, stop_price: Price) {
+        let (side, current, sequence) = match self.index.get_mut(&id) {
+            Some(entry) => entry,
+            None => return,
+        };
+        let favourable = match side {
+            Side::Buy => stop_price < *current,
+            Side::Sell => stop_price > *current,
+        };
+        if !favourable {
+            return;
+        }
+        let stops = match side {
+            Side::Buy => &mut self.buys,
+            Side::Sell => &mut self.sells,
+        };
+        let order = stops.remove(&(*current, *sequence)).unwrap();
+        stops.insert((stop_price, *sequence), order);
+        *current = stop_price;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Returns the id of the earliest-arrived stop triggered by the market having traded as high
+    /// as `high` and as low as `low`, if any.
+    pub(crate) fn next_triggered(&self, high: Price, low: Price) -> Option<OrderId> {
+        let buys = self.buys.range(..=(high, u64::MAX));
+        let sells = self.sells.range((low, 0)..);
+        buys.chain(sells)
+            .min_by_key(|((_, sequence), _)| *sequence)
+            .map(|(_, order)| order.id)
+    }
+}
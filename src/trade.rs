@@ -1,9 +1,115 @@
-use crate::types::{Price, Quantity, OrderId, 
-                   Side, OrderType};
+use std::collections::HashMap;
+use std::ops::{Bound, RangeBounds};
+
+use crate::types::{Price, Quantity, OrderId, Timestamp};
 use crate::order::Order;
+
+pub type TradeId = u64;
+
+/// Trade is a single execution between a buy order and a sell order.
+/// The orders are recorded as they were just before the trade.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Trade {
+    pub id: TradeId,
+    pub sequence: u64, // book sequence number at which the trade happened
+    pub timestamp: Timestamp,
     pub buy_order: Order,
     pub sell_order: Order,
     pub price: Price,
     pub quantity: Quantity,
-}
\ No newline at end of file
+}
+
+/// TradeLog is the append-only record of every trade on a book, in execution order.
+/// Trade ids start at 1 and increase by one with every trade, so a trade's id also locates it in the log.
+#[derive(Debug, Default)]
+pub struct TradeLog {
+    trades: Vec<Trade>,
+    by_order: HashMap<OrderId, Vec<usize>>, // order id -> indices of the trades it took part in
+}
+
+impl TradeLog {
+
+    pub fn new() -> TradeLog {
+        TradeLog {
+            trades: Vec::new(),
+            by_order: HashMap::new(),
+        }
+    }
+
+    /// Id the next recorded trade will have.
+    pub fn next_id(&self) -> TradeId {
+        self.trades.len() as TradeId + 1
+    }
+
+    pub(crate) fn push(&mut self, trade: Trade) {
+        assert_eq!(trade.id, self.next_id(), "trades must be recorded in id order");
+        let index = self.trades.len();
+        self.by_order.entry(trade.buy_order.id).or_default().push(index);
+        self.by_order.entry(trade.sell_order.id).or_default().push(index);
+        self.trades.push(trade);
+    }
+
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Trade> {
+        self.trades.last()
+    }
+
+    /// Iterates over every trade in execution order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Trade> {
+        self.trades.iter()
+    }
+
+    pub fn get(&self, id: TradeId) -> Option<&Trade> {
+        let index = id.checked_sub(1)?;
+        self.trades.get(index as usize)
+    }
+
+    /// Every trade an order took part in, on either side, in execution order.
+    pub fn by_order(&self, id: OrderId) -> impl Iterator<Item = &Trade> + '_ {
+        self.by_order.get(&id)
+            .into_iter()
+            .flatten()
+            .map(move |&index| &self.trades[index])
+    }
+
+    /// Every trade between a given buy order and sell order, in execution order.
+    pub fn between(&self, buy_order: OrderId, sell_order: OrderId) -> impl Iterator<Item = &Trade> + '_ {
+        self.by_order(buy_order)
+            .filter(move |trade| trade.buy_order.id == buy_order && trade.sell_order.id == sell_order)
+    }
+
+    /// The trades whose ids fall in `ids`, in execution order.
+    pub fn range<R: RangeBounds<TradeId>>(&self, ids: R) -> &[Trade] {
+        let len = self.trades.len();
+        // ids start at 1, so trade id n is at index n - 1
+        let start = match ids.start_bound() {
+            Bound::Included(&id) => id.saturating_sub(1),
+            Bound::Excluded(&id) => id,
+            Bound::Unbounded => 0,
+        };
+        let end = match ids.end_bound() {
+            Bound::Included(&id) => id,
+            Bound::Excluded(&id) => id.saturating_sub(1),
+            Bound::Unbounded => len as TradeId,
+        };
+        let start = (start as usize).min(len);
+        let end = (end as usize).clamp(start, len);
+        &self.trades[start..end]
+    }
+}
+
+impl<'a> IntoIterator for &'a TradeLog {
+    type Item = &'a Trade;
+    type IntoIter = std::slice::Iter<'a, Trade>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.trades.iter()
+    }
+}
@@ -1,53 +1,441 @@
-use std::cmp::Ordering;
-use std::hash::{Hash, Hasher};
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
 
 pub type Quantity = usize;
 pub type OrderId = i32;
+pub type Timestamp = u64; // nanoseconds since the Unix epoch
+pub type AccountId = u64;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Price(pub f64);
+/// Number of decimal places a Price is stored with.
+pub const PRICE_DECIMALS: u32 = 6;
+const PRICE_SCALE: i64 = 10i64.pow(PRICE_DECIMALS);
 
-impl PartialEq for Price {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+/// Price is a fixed-point decimal stored as an integer number of 10^-PRICE_DECIMALS units,
+/// so prices hash, compare and add exactly. Whether a price is valid for a given book is
+/// decided by the book's tick size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Price(i64);
+
+impl Price {
+    pub const ZERO: Price = Price(0);
+
+    /// Create a price from its raw fixed-point representation, in 10^-PRICE_DECIMALS units.
+    pub const fn from_raw(raw: i64) -> Price {
+        Price(raw)
+    }
+
+    /// Create a price from a whole number of currency units.
+    pub const fn from_int(value: i64) -> Price {
+        Price(value * PRICE_SCALE)
+    }
+
+    /// Create a price from a float, rounding to the nearest 10^-PRICE_DECIMALS.
+    /// Returns None for NaN, infinite or out of range values.
+    pub fn from_f64(value: f64) -> Option<Price> {
+        let raw = (value * PRICE_SCALE as f64).round();
+        if raw.is_finite() && raw >= i64::MIN as f64 && raw < i64::MAX as f64 {
+            Some(Price(raw as i64))
+        } else {
+            None
+        }
+    }
+
+    pub const fn raw(&self) -> i64 {
+        self.0
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0 as f64 / PRICE_SCALE as f64
+    }
+
+    /// Returns true if the price lies on a multiple of `tick_size`.
+    pub fn is_on_tick(&self, tick_size: Price) -> bool {
+        tick_size.0 > 0 && self.0 % tick_size.0 == 0
+    }
+
+    /// Rounds down to the nearest multiple of `tick_size`.
+    pub fn floor_to_tick(&self, tick_size: Price) -> Price {
+        Price(self.0 - self.0.rem_euclid(tick_size.0))
+    }
+
+    /// Rounds up to the nearest multiple of `tick_size`.
+    pub fn ceil_to_tick(&self, tick_size: Price) -> Price {
+        -(-*self).floor_to_tick(tick_size)
+    }
+}
+
+impl Add for Price {
+    type Output = Price;
+
+    fn add(self, other: Price) -> Price {
+        Price(self.0 + other.0)
     }
 }
 
-impl Hash for Price {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        // Convert the f64 to a format that can be hashed
-        let price_as_int = (self.0 * 100.0) as i64;
-        price_as_int.hash(state);
+impl Sub for Price {
+    type Output = Price;
+
+    fn sub(self, other: Price) -> Price {
+        Price(self.0 - other.0)
     }
 }
 
-impl PartialOrd for Price {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
+impl Neg for Price {
+    type Output = Price;
+
+    fn neg(self) -> Price {
+        Price(-self.0)
     }
 }
 
-impl Eq for Price {}
+impl Mul<i64> for Price {
+    type Output = Price;
 
-impl Ord for Price {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.partial_cmp(other) {
-            Some(ordering) => ordering,
-            None => Ordering::Less,
+    fn mul(self, ticks: i64) -> Price {
+        Price(self.0 * ticks)
+    }
+}
+
+impl TryFrom<f64> for Price {
+    type Error = PriceError;
+
+    fn try_from(value: f64) -> Result<Price, PriceError> {
+        Price::from_f64(value).ok_or(PriceError::OutOfRange)
+    }
+}
+
+impl From<Price> for f64 {
+    fn from(price: Price) -> f64 {
+        price.to_f64()
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let whole = (self.0 / PRICE_SCALE).unsigned_abs();
+        let fraction = (self.0 % PRICE_SCALE).unsigned_abs();
+        if fraction == 0 {
+            return write!(f, "{}{}", sign, whole);
+        }
+        let digits = format!("{:0width$}", fraction, width = PRICE_DECIMALS as usize);
+        write!(f, "{}{}.{}", sign, whole, digits.trim_end_matches('0'))
+    }
+}
+
+impl FromStr for Price {
+    type Err = PriceError;
+
+    /// Parses a decimal string such as "100", "-0.25" or "99.950". Strings with more than
+    /// PRICE_DECIMALS significant decimal places are rejected rather than rounded.
+    fn from_str(s: &str) -> Result<Price, PriceError> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (digits, ""),
+        };
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(PriceError::Invalid);
+        }
+        if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(PriceError::Invalid);
+        }
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > PRICE_DECIMALS as usize {
+            return Err(PriceError::TooPrecise);
+        }
+
+        let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| PriceError::OutOfRange)? };
+        let fraction: i64 = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<i64>().unwrap() * 10i64.pow(PRICE_DECIMALS - fraction.len() as u32)
+        };
+        let raw = whole.checked_mul(PRICE_SCALE)
+            .and_then(|raw| raw.checked_add(fraction))
+            .ok_or(PriceError::OutOfRange)?;
+        Ok(Price(if negative { -raw } else { raw }))
+    }
+}
+
+/// Reasons a value cannot be converted into a Price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceError {
+    Invalid,
+    OutOfRange,
+    TooPrecise,
+}
+
+impl fmt::Display for PriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceError::Invalid => write!(f, "not a decimal number"),
+            PriceError::OutOfRange => write!(f, "price out of range"),
+            PriceError::TooPrecise => write!(f, "price has more than {} decimal places", PRICE_DECIMALS),
         }
     }
 }
 
+impl std::error::Error for PriceError {}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Side {
     Buy,
     Sell,
 }
 
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum OrderType {
     GTC,
     FOK,
     IOC,
     Market,
-}
\ No newline at end of file
+    GTD, // rests until its expiry time, like a GTC order
+    Day, // rests until the book's trading session ends
+    StopMarket, // held off the book until its stop price trades, then placed as a market order
+    StopLimit,  // held off the book until its stop price trades, then placed as a GTC limit order
+    TrailingStopMarket, // a stop market order whose stop price trails the market
+    TrailingStopLimit,  // a stop limit order whose stop and limit prices trail the market
+}
+
+impl OrderType {
+    /// Returns false for order types that ignore the order's price.
+    pub fn has_limit_price(&self) -> bool {
+        !matches!(self, OrderType::Market | OrderType::StopMarket) && !self.is_trailing()
+    }
+
+    /// Returns true for the order types held off the book until a trade reaches their stop price.
+    pub fn is_stop(&self) -> bool {
+        matches!(self, OrderType::StopMarket | OrderType::StopLimit) || self.is_trailing()
+    }
+
+    /// Returns true for the order types that may rest on the book, directly or once triggered.
+    pub fn can_rest(&self) -> bool {
+        matches!(self, OrderType::GTC | OrderType::GTD | OrderType::Day
+            | OrderType::StopLimit | OrderType::TrailingStopLimit)
+    }
+
+    pub fn is_trailing(&self) -> bool {
+        matches!(self, OrderType::TrailingStopMarket | OrderType::TrailingStopLimit)
+    }
+}
+
+/// How far the stop price of a trailing stop order stays behind its reference price.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TrailOffset {
+    Amount(Price),    // a fixed price distance
+    BasisPoints(u32), // a fraction of the reference price, in hundredths of a percent
+}
+
+/// The price a trailing stop order follows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TrailReference {
+    LastTrade, // the price of every trade on the book
+    BestPrice, // the best bid for sell orders and the best ask for buy orders
+}
+
+/// Trail describes how the stop price of a trailing stop order follows the market.
+/// The stop price only ever moves in the order's favour: up for sell orders as the reference
+/// price rises, and down for buy orders as it falls.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Trail {
+    pub offset: TrailOffset,
+    pub reference: TrailReference,
+    pub limit_offset: Price, // distance of a trailing stop limit order's limit price beyond its stop price
+}
+
+impl Trail {
+    pub fn new(offset: TrailOffset, reference: TrailReference) -> Trail {
+        Trail {
+            offset,
+            reference,
+            limit_offset: Price::ZERO,
+        }
+    }
+
+    /// Sets how far beyond the stop price a triggered trailing stop limit order is priced.
+    pub fn with_limit_offset(self, limit_offset: Price) -> Trail {
+        Trail {
+            limit_offset,
+            ..self
+        }
+    }
+
+    /// Returns the stop price trailing `reference` for an order on `side`, rounded away from
+    /// the reference price onto the tick grid.
+    pub fn stop_price(&self, side: Side, reference: Price, tick_size: Price) -> Price {
+        let offset = match self.offset {
+            TrailOffset::Amount(amount) => amount,
+            TrailOffset::BasisPoints(bps) => {
+                Price::from_raw((reference.raw() as i128 * bps as i128 / 10_000) as i64)
+            }
+        };
+        match side {
+            Side::Buy => (reference + offset).ceil_to_tick(tick_size),
+            Side::Sell => (reference - offset).floor_to_tick(tick_size),
+        }
+    }
+
+    /// Returns the limit price of a triggered trailing stop limit order.
+    pub fn limit_price(&self, side: Side, stop_price: Price) -> Price {
+        match side {
+            Side::Buy => stop_price + self.limit_offset,
+            Side::Sell => stop_price - self.limit_offset,
+        }
+    }
+}
+
+/// What the book does when an incoming order would trade with a resting order of the same owner.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SelfTradePrevention {
+    CancelNewest,       // cancel the rest of the incoming order
+    CancelOldest,       // cancel the resting order and keep matching
+    CancelBoth,         // cancel the incoming order and the resting order
+    DecrementAndCancel, // take the smaller quantity off both, cancelling whichever reaches zero
+}
+
+/// TradingRules constrain the size of the orders a book accepts.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TradingRules {
+    pub lot_size: Quantity,             // quantities must be a multiple of it
+    pub min_quantity: Quantity,
+    pub max_quantity: Option<Quantity>,
+    pub max_notional: Option<Price>,    // largest price times quantity of a limit order
+}
+
+impl Default for TradingRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TradingRules {
+    /// Rules that accept any quantity.
+    pub fn new() -> TradingRules {
+        TradingRules {
+            lot_size: 1,
+            min_quantity: 1,
+            max_quantity: None,
+            max_notional: None,
+        }
+    }
+
+    pub fn with_lot_size(self, lot_size: Quantity) -> TradingRules {
+        assert!(lot_size > 0, "lot size must be positive");
+        TradingRules { lot_size, ..self }
+    }
+
+    pub fn with_min_quantity(self, min_quantity: Quantity) -> TradingRules {
+        TradingRules { min_quantity, ..self }
+    }
+
+    pub fn with_max_quantity(self, max_quantity: Quantity) -> TradingRules {
+        TradingRules {
+            max_quantity: Some(max_quantity),
+            ..self
+        }
+    }
+
+    pub fn with_max_notional(self, max_notional: Price) -> TradingRules {
+        TradingRules {
+            max_notional: Some(max_notional),
+            ..self
+        }
+    }
+}
+
+/// What the book does with a post-only order that would take liquidity when it is placed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PostOnly {
+    Reject, // reject the order
+    Slide,  // reprice the order one tick behind the best opposite price and rest it
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn price_rounds_to_tick() {
+        let tick = Price::from_raw(250_000);
+        assert_eq!(Price::from_raw(1_000_000), Price::from_raw(1_100_000).floor_to_tick(tick));
+        assert_eq!(Price::from_raw(1_250_000), Price::from_raw(1_100_000).ceil_to_tick(tick));
+        assert_eq!(Price::from_raw(-1_250_000), Price::from_raw(-1_100_000).floor_to_tick(tick));
+        assert_eq!(Price::from_raw(-1_000_000), Price::from_raw(-1_100_000).ceil_to_tick(tick));
+        assert_eq!(Price::from_int(1), Price::from_int(1).ceil_to_tick(tick));
+    }
+
+    #[test]
+    fn trail_stop_price() {
+        let tick = Price::from_raw(10_000);
+        let amount = Trail::new(TrailOffset::Amount(Price::from_int(2)), TrailReference::LastTrade);
+        assert_eq!(Price::from_int(98), amount.stop_price(Side::Sell, Price::from_int(100), tick));
+        assert_eq!(Price::from_int(102), amount.stop_price(Side::Buy, Price::from_int(100), tick));
+
+        // 1.5% of 10.01 is 0.15015, rounded away from the reference
+        let percent = Trail::new(TrailOffset::BasisPoints(150), TrailReference::LastTrade);
+        assert_eq!(Price::from_raw(9_850_000), percent.stop_price(Side::Sell, Price::from_raw(10_010_000), tick));
+        assert_eq!(Price::from_raw(10_170_000), percent.stop_price(Side::Buy, Price::from_raw(10_010_000), tick));
+    }
+
+    #[test]
+    fn price_string_round_trip() {
+        for s in ["100", "100.5", "-0.25", "0.000001", "99.95", "-12345.678901"] {
+            let price: Price = s.parse().unwrap();
+            assert_eq!(s, price.to_string());
+        }
+        assert_eq!(Price::from_int(100), "100.000".parse().unwrap());
+        assert_eq!(Price::from_raw(500_000), ".5".parse().unwrap());
+    }
+
+    #[test]
+    fn price_rejects_bad_strings() {
+        assert_eq!(Err(PriceError::TooPrecise), "1.0000001".parse::<Price>());
+        assert_eq!(Err(PriceError::Invalid), "abc".parse::<Price>());
+        assert_eq!(Err(PriceError::Invalid), "".parse::<Price>());
+        assert_eq!(Err(PriceError::Invalid), "1.2.3".parse::<Price>());
+        assert_eq!(Err(PriceError::OutOfRange), "99999999999999999999".parse::<Price>());
+    }
+
+    #[test]
+    fn price_f64_round_trip() {
+        let price = Price::from_f64(100.001).unwrap();
+        assert_eq!(Price::from_raw(100_001_000), price);
+        assert_eq!(100.001, price.to_f64());
+        assert_eq!(Some(price), Price::from_f64(price.to_f64()));
+        assert_eq!(None, Price::from_f64(f64::NAN));
+        assert_eq!(None, Price::from_f64(f64::INFINITY));
+    }
+
+    #[test]
+    fn distinct_prices_hash_distinctly() {
+        let prices: HashSet<Price> = [100.001, 100.004, 100.0].iter()
+            .map(|&p| Price::from_f64(p).unwrap())
+            .collect();
+        assert_eq!(3, prices.len());
+        assert!(Price::from_f64(100.001).unwrap() < Price::from_f64(100.004).unwrap());
+    }
+
+    #[test]
+    fn price_on_tick() {
+        let tick: Price = "0.05".parse().unwrap();
+        assert!(Price::from_int(100).is_on_tick(tick));
+        assert!("99.95".parse::<Price>().unwrap().is_on_tick(tick));
+        assert!(!"99.96".parse::<Price>().unwrap().is_on_tick(tick));
+        assert!(!Price::from_int(100).is_on_tick(Price::ZERO));
+    }
+}
@@ -0,0 +1,119 @@
+use ac_rust_orderbook::types::{Price, Quantity, Side, OrderType, SelfTradePrevention, TradingRules};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::{ExecutionReport, OrderStatus};
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::allocation::{Hybrid, ProRata, Rounding};
+
+fn pro_rata_book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.set_allocation(ProRata::new(0, Rounding::Down));
+    orderbook
+}
+
+fn ask(id: i32, quantity: Quantity) -> Order {
+    Order::new(id, OrderType::GTC, quantity, Price::from_int(10), Side::Sell)
+}
+
+fn buy(id: i32, quantity: Quantity) -> Order {
+    Order::new(id, OrderType::IOC, quantity, Price::from_int(10), Side::Buy)
+}
+
+fn fills(report: &ExecutionReport) -> Vec<(i32, Quantity)> {
+    report.fills.iter().map(|fill| (fill.counterparty, fill.quantity)).collect()
+}
+
+#[test]
+fn pro_rata_shares_by_size() {
+    let mut orderbook = pro_rata_book();
+    orderbook.place_order(ask(1, 100)).unwrap();
+    orderbook.place_order(ask(2, 300)).unwrap();
+    let report = orderbook.place_order(buy(3, 200)).unwrap();
+    assert_eq!(fills(&report), vec![(1, 50), (2, 150)]);
+    assert_eq!(orderbook.get_order(1).unwrap().order.quantity, 50);
+    assert_eq!(orderbook.get_order(2).unwrap().order.quantity, 150);
+    assert_eq!(orderbook.sell_volume, 200);
+}
+
+#[test]
+fn hybrid_serves_top_order_then_market_makers_then_pro_rata() {
+    let mut orderbook = OrderBook::new();
+    orderbook.set_allocation(Hybrid::new(ProRata::new(0, Rounding::Down))
+        .with_top_order()
+        .with_market_makers(&[9], 20));
+    orderbook.place_order(ask(1, 100).with_owner(1)).unwrap();
+    orderbook.place_order(ask(2, 200).with_owner(2)).unwrap();
+    orderbook.place_order(ask(3, 200).with_owner(9)).unwrap();
+    // 100 to the top order, 40 to the market maker, then 160 shared over 200 and 160
+    let report = orderbook.place_order(buy(4, 300)).unwrap();
+    assert_eq!(fills(&report), vec![(1, 100), (2, 89), (3, 111)]);
+}
+
+#[test]
+fn pro_rata_keeps_resting_orders_in_whole_lots() {
+    let mut orderbook = pro_rata_book();
+    orderbook.set_trading_rules(TradingRules::new().with_lot_size(10));
+    orderbook.place_order(ask(1, 30)).unwrap();
+    orderbook.place_order(ask(2, 30)).unwrap();
+    orderbook.place_order(ask(3, 40)).unwrap();
+    let report = orderbook.place_order(buy(4, 50)).unwrap();
+    assert_eq!(fills(&report), vec![(1, 20), (2, 10), (3, 20)]);
+    let left: Vec<Quantity> = orderbook.ask_orders().map(|order| order.quantity).collect();
+    assert_eq!(left, vec![10, 20, 20]);
+}
+
+#[test]
+fn level_is_allocated_again_after_an_iceberg_shows_a_new_peak() {
+    let mut orderbook = pro_rata_book();
+    orderbook.place_order(ask(1, 100).with_display_quantity(20)).unwrap();
+    orderbook.place_order(ask(2, 20)).unwrap();
+    let report = orderbook.place_order(buy(3, 60)).unwrap();
+    assert_eq!(fills(&report), vec![(1, 20), (2, 20), (1, 20)]);
+    assert_eq!(orderbook.sell_volume, 20);
+    assert_eq!(orderbook.sell_hidden_volume, 40);
+}
+
+#[test]
+fn self_trade_is_prevented_before_the_level_is_allocated_again() {
+    let mut orderbook = pro_rata_book();
+    orderbook.set_self_trade_prevention(Some(SelfTradePrevention::CancelOldest));
+    orderbook.place_order(ask(1, 100).with_owner(5)).unwrap();
+    orderbook.place_order(ask(2, 100).with_owner(6)).unwrap();
+    let report = orderbook.place_order(buy(3, 100).with_owner(5)).unwrap();
+    assert_eq!(report.self_trade_prevented.len(), 1);
+    assert_eq!(fills(&report), vec![(2, 100)]);
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn fill_or_kill_follows_the_allocation() {
+    let mut orderbook = pro_rata_book();
+    orderbook.place_order(ask(1, 60).with_all_or_none()).unwrap();
+    orderbook.place_order(ask(2, 40)).unwrap();
+    // a pro-rata share of 80 never fills the all-or-none order, so only 40 is available
+    let fok = |id, quantity| Order::new(id, OrderType::FOK, quantity, Price::from_int(10), Side::Buy);
+    let report = orderbook.place_order(fok(3, 80)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    let report = orderbook.place_order(fok(4, 100)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(fills(&report), vec![(1, 60), (2, 40)]);
+}
+
+#[test]
+fn fill_or_kill_is_allocated_on_displayed_quantities() {
+    let mut orderbook = pro_rata_book();
+    orderbook.set_self_trade_prevention(Some(SelfTradePrevention::CancelNewest));
+    orderbook.place_order(ask(1, 100).with_display_quantity(10).with_owner(1)).unwrap();
+    orderbook.place_order(ask(2, 10).with_owner(2)).unwrap();
+    let fok = |id, quantity| Order::new(id, OrderType::FOK, quantity, Price::from_int(10), Side::Buy).with_owner(2);
+    // the shown 10 and 10 share the order half and half, so the own order would be reached
+    let report = orderbook.place_order(fok(3, 10)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.sell_volume, 20);
+
+    orderbook.cancel_order(2).unwrap();
+    // the iceberg fills 30 over three peaks
+    let report = orderbook.place_order(fok(4, 30)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(fills(&report), vec![(1, 10), (1, 10), (1, 10)]);
+}
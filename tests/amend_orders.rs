@@ -0,0 +1,131 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, PostOnly};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::error::OrderBookError;
+use ac_rust_orderbook::report::{OrderStatus, RejectReason};
+use ac_rust_orderbook::orderbook::OrderBook;
+
+fn two_bids_at_same_price() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+    orderbook
+}
+
+fn counterparties_of_sell(orderbook: &mut OrderBook, id: i32, quantity: usize) -> Vec<i32> {
+    let report = orderbook.place_order(Order::new(id, OrderType::GTC, quantity, Price::from_int(30), Side::Sell)).unwrap();
+    report.fills.iter().map(|fill| fill.counterparty).collect()
+}
+
+#[test]
+fn quantity_decrease_keeps_priority() {
+    let mut orderbook = two_bids_at_same_price();
+
+    let report = orderbook.amend_order(1, Price::from_int(30), 40).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(report.remaining_quantity, 40);
+    assert_eq!(orderbook.buy_volume, 140);
+    assert_eq!(orderbook.bid_levels().collect::<Vec<_>>(), vec![(Price::from_int(30), 140)]);
+
+    assert_eq!(counterparties_of_sell(&mut orderbook, 3, 50), vec![1, 2]);
+}
+
+#[test]
+fn quantity_increase_loses_priority() {
+    let mut orderbook = two_bids_at_same_price();
+
+    orderbook.amend_order(1, Price::from_int(30), 150).unwrap();
+    assert_eq!(orderbook.buy_volume, 250);
+
+    assert_eq!(counterparties_of_sell(&mut orderbook, 3, 150), vec![2, 1]);
+}
+
+#[test]
+fn price_change_loses_priority() {
+    let mut orderbook = two_bids_at_same_price();
+
+    orderbook.amend_order(1, Price::from_int(29), 100).unwrap();
+    orderbook.amend_order(1, Price::from_int(30), 100).unwrap();
+    assert_eq!(orderbook.bid_levels().collect::<Vec<_>>(), vec![(Price::from_int(30), 200)]);
+
+    assert_eq!(counterparties_of_sell(&mut orderbook, 3, 150), vec![2, 1]);
+}
+
+#[test]
+fn amend_through_the_spread_matches() {
+    let mut orderbook = two_bids_at_same_price();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 60, Price::from_int(31), Side::Sell)).unwrap();
+
+    let report = orderbook.amend_order(2, Price::from_int(31), 100).unwrap();
+    assert_eq!(report.status, OrderStatus::PartiallyFilled);
+    assert_eq!(report.filled_quantity, 60);
+    assert_eq!(report.remaining_quantity, 40);
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(31));
+    assert_eq!(orderbook.get_ask(), None);
+    assert_eq!(orderbook.buy_volume, 140);
+    assert_eq!(orderbook.sell_volume, 0);
+}
+
+#[test]
+fn invalid_amends_leave_the_order_alone() {
+    let mut orderbook = two_bids_at_same_price();
+
+    assert_eq!(orderbook.amend_order(7, Price::from_int(30), 10), Err(OrderBookError::UnknownOrderId(7)));
+    assert_eq!(orderbook.amend_order(1, Price::from_int(30), 0), Err(OrderBookError::ZeroQuantity));
+    let off_tick = "30.001".parse().unwrap();
+    assert_eq!(orderbook.amend_order(1, off_tick, 10), Err(OrderBookError::OffTick(off_tick)));
+
+    assert_eq!(orderbook.buy_volume, 200);
+    assert_eq!(counterparties_of_sell(&mut orderbook, 3, 150), vec![1, 2]);
+}
+
+#[test]
+fn cancel_replace_assigns_new_id() {
+    let mut orderbook = two_bids_at_same_price();
+
+    let replacement = Order::new(3, OrderType::GTC, 80, Price::from_int(30), Side::Buy);
+    let report = orderbook.cancel_replace(1, replacement).unwrap();
+    assert_eq!(report.order_id, 3);
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(orderbook.cancel_order(1), Err(OrderBookError::UnknownOrderId(1)));
+    assert_eq!(orderbook.buy_volume, 180);
+
+    assert_eq!(counterparties_of_sell(&mut orderbook, 4, 150), vec![2, 3]);
+}
+
+#[test]
+fn rejected_cancel_replace_keeps_original() {
+    let mut orderbook = two_bids_at_same_price();
+
+    let replacement = Order::new(2, OrderType::GTC, 80, Price::from_int(30), Side::Buy);
+    assert_eq!(orderbook.cancel_replace(1, replacement), Err(OrderBookError::DuplicateOrderId(2)));
+    let replacement = Order::new(3, OrderType::GTC, 80, Price::from_int(30), Side::Buy);
+    assert_eq!(orderbook.cancel_replace(5, replacement), Err(OrderBookError::UnknownOrderId(5)));
+
+    // replacements rejected on arrival leave the original in place too
+    let replacement = Order::new(3, OrderType::FOK, 80, Price::from_int(30), Side::Buy);
+    let report = orderbook.cancel_replace(1, replacement).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(report.reject_reason, Some(RejectReason::InsufficientLiquidity));
+    let replacement = Order::new(3, OrderType::GTC, 80, Price::from_int(30), Side::Sell)
+        .with_post_only(PostOnly::Reject);
+    let report = orderbook.cancel_replace(1, replacement).unwrap();
+    assert_eq!(report.reject_reason, Some(RejectReason::WouldTakeLiquidity));
+    assert_eq!(orderbook.get_order(1).unwrap().queue_position, 0);
+
+    assert_eq!(orderbook.buy_volume, 200);
+    assert_eq!(counterparties_of_sell(&mut orderbook, 4, 150), vec![1, 2]);
+}
+
+#[test]
+fn cancel_replace_is_checked_without_the_original() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+
+    // the post-only sell only crosses the bid it replaces
+    let replacement = Order::new(2, OrderType::GTC, 50, Price::from_int(30), Side::Sell)
+        .with_post_only(PostOnly::Reject);
+    let report = orderbook.cancel_replace(1, replacement).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(orderbook.get_bid(), None);
+    assert_eq!(orderbook.get_ask(), Some(&Price::from_int(30)));
+}
@@ -0,0 +1,140 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::events::{BookEvent, CancelReason};
+
+fn recording_book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.set_event_recording(true);
+    orderbook
+}
+
+fn events(orderbook: &mut OrderBook) -> Vec<BookEvent> {
+    orderbook.drain_events().into_iter().map(|event| event.event).collect()
+}
+
+fn level(side: Side, price: i64, quantity: usize, order_count: usize) -> BookEvent {
+    BookEvent::LevelChanged { side, price: Price::from_int(price), quantity, order_count }
+}
+
+#[test]
+fn nothing_is_recorded_by_default() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    assert!(orderbook.drain_events().is_empty());
+    assert_eq!(orderbook.event_sequence(), 0);
+}
+
+#[test]
+fn resting_order_is_added() {
+    let mut orderbook = recording_book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    assert_eq!(events(&mut orderbook), vec![
+        BookEvent::OrderAdded { order_id: 1, side: Side::Sell, price: Price::from_int(30), quantity: 100 },
+        level(Side::Sell, 30, 100, 1),
+    ]);
+}
+
+#[test]
+fn trade_executes_the_resting_order() {
+    let mut orderbook = recording_book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.drain_events();
+
+    orderbook.place_order(Order::new(2, OrderType::IOC, 40, Price::from_int(31), Side::Buy)).unwrap();
+    assert_eq!(events(&mut orderbook), vec![
+        BookEvent::Trade {
+            trade_id: 1,
+            price: Price::from_int(30),
+            quantity: 40,
+            buy_order_id: 2,
+            sell_order_id: 1,
+            aggressor: Side::Buy,
+        },
+        BookEvent::OrderExecuted { order_id: 1, side: Side::Sell, price: Price::from_int(30), quantity: 40 },
+        level(Side::Sell, 30, 60, 1),
+    ]);
+}
+
+#[test]
+fn cancels_carry_their_reason() {
+    let mut orderbook = recording_book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.drain_events();
+
+    orderbook.amend_order(1, Price::from_int(30), 60).unwrap();
+    orderbook.cancel_order(2).unwrap();
+    assert_eq!(events(&mut orderbook), vec![
+        BookEvent::OrderCancelled {
+            order_id: 1,
+            side: Side::Sell,
+            price: Price::from_int(30),
+            quantity: 40,
+            reason: CancelReason::Amended,
+        },
+        level(Side::Sell, 30, 160, 2),
+        BookEvent::OrderCancelled {
+            order_id: 2,
+            side: Side::Sell,
+            price: Price::from_int(30),
+            quantity: 100,
+            reason: CancelReason::Cancelled,
+        },
+        level(Side::Sell, 30, 60, 1),
+    ]);
+
+    orderbook.cancel_replace(1, Order::new(3, OrderType::GTC, 50, Price::from_int(31), Side::Sell)).unwrap();
+    assert_eq!(events(&mut orderbook)[..2], [
+        BookEvent::OrderCancelled {
+            order_id: 1,
+            side: Side::Sell,
+            price: Price::from_int(30),
+            quantity: 60,
+            reason: CancelReason::Replaced,
+        },
+        level(Side::Sell, 30, 0, 0),
+    ]);
+}
+
+#[test]
+fn amend_to_a_new_price_moves_the_order() {
+    let mut orderbook = recording_book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.drain_events();
+
+    orderbook.amend_order(1, Price::from_int(31), 100).unwrap();
+    let events = events(&mut orderbook);
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[1], level(Side::Sell, 30, 0, 0));
+    assert_eq!(events[3], level(Side::Sell, 31, 100, 1));
+}
+
+#[test]
+fn replenished_iceberg_is_added_again() {
+    let mut orderbook = recording_book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 300, Price::from_int(30), Side::Sell).with_display_quantity(100)).unwrap();
+    orderbook.drain_events();
+
+    orderbook.place_order(Order::new(2, OrderType::IOC, 100, Price::from_int(30), Side::Buy)).unwrap();
+    let events = events(&mut orderbook);
+    assert_eq!(events[1..], [
+        BookEvent::OrderExecuted { order_id: 1, side: Side::Sell, price: Price::from_int(30), quantity: 100 },
+        BookEvent::OrderAdded { order_id: 1, side: Side::Sell, price: Price::from_int(30), quantity: 100 },
+        level(Side::Sell, 30, 100, 1),
+    ]);
+}
+
+#[test]
+fn sequence_numbers_have_no_gaps() {
+    let mut orderbook = recording_book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    let first = orderbook.drain_events();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 150, Price::from_int(30), Side::Buy)).unwrap();
+    orderbook.cancel_order(2).unwrap();
+    let second = orderbook.drain_events();
+
+    let sequences: Vec<_> = first.iter().chain(second.iter()).map(|event| event.sequence).collect();
+    assert_eq!(sequences, (1..=sequences.len() as u64).collect::<Vec<_>>());
+    assert_eq!(orderbook.event_sequence(), sequences.len() as u64);
+}
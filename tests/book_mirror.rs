@@ -0,0 +1,155 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, SelfTradePrevention, PostOnly};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::events::{BookEvent, SequencedEvent};
+use ac_rust_orderbook::mirror::{BookMirror, MirrorError};
+use ac_rust_orderbook::depth::DepthLevel;
+
+/// xorshift64*, so the order flow is the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn random_order(rng: &mut Rng, id: i32) -> Order {
+    let side = if rng.below(2) == 0 { Side::Buy } else { Side::Sell };
+    let price = Price::from_int(95 + rng.below(11) as i64);
+    let quantity = 1 + rng.below(100) as usize;
+    let kind = match rng.below(10) {
+        0 => OrderType::IOC,
+        1 => OrderType::FOK,
+        2 => OrderType::Market,
+        3 => OrderType::StopLimit,
+        _ => OrderType::GTC,
+    };
+    let mut order = Order::new(id, kind, quantity, price, side).with_owner(rng.below(4));
+    if kind == OrderType::StopLimit {
+        order = order.with_stop_price(Price::from_int(95 + rng.below(11) as i64));
+    }
+    match rng.below(8) {
+        0 => order.with_display_quantity(1 + rng.below(20) as usize),
+        1 if kind == OrderType::GTC => order.with_post_only(PostOnly::Slide),
+        _ => order,
+    }
+}
+
+fn visible_orders<'a>(orders: impl Iterator<Item = &'a Order>) -> Vec<(i32, Price, usize)> {
+    orders.map(|order| (order.id, order.price, order.quantity)).collect()
+}
+
+fn visible_levels(levels: impl Iterator<Item = DepthLevel>) -> Vec<(Price, usize, usize)> {
+    levels.map(|level| (level.price, level.quantity, level.order_count)).collect()
+}
+
+fn assert_mirrors(orderbook: &OrderBook, mirror: &BookMirror) {
+    assert_eq!(visible_orders(orderbook.bid_orders()), visible_orders(mirror.bid_orders()));
+    assert_eq!(visible_orders(orderbook.ask_orders()), visible_orders(mirror.ask_orders()));
+    assert_eq!(visible_levels(orderbook.bid_depth()), visible_levels(mirror.bid_depth()));
+    assert_eq!(visible_levels(orderbook.ask_depth()), visible_levels(mirror.ask_depth()));
+    assert_eq!(orderbook.last_trade_price(), mirror.last_trade_price());
+    assert_eq!(orderbook.event_sequence(), mirror.sequence());
+}
+
+fn run_random_flow(orderbook: &mut OrderBook, rng: &mut Rng, first_id: i32, steps: i32, mut step: impl FnMut(&mut OrderBook)) {
+    for id in first_id..first_id + steps {
+        match rng.below(4) {
+            0 => {
+                let _ = orderbook.cancel_order(1 + rng.below(id as u64) as i32);
+            }
+            1 => {
+                let target = 1 + rng.below(id as u64) as i32;
+                let price = Price::from_int(95 + rng.below(11) as i64);
+                let _ = orderbook.amend_order(target, price, 1 + rng.below(100) as usize);
+            }
+            _ => {
+                let order = random_order(rng, id);
+                let _ = orderbook.place_order(order);
+            }
+        }
+        step(orderbook);
+    }
+}
+
+#[test]
+fn mirror_follows_randomized_order_flow() {
+    for seed in 1..=20u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let mut orderbook = OrderBook::new();
+        orderbook.set_event_recording(true);
+        let mode = match seed % 3 {
+            0 => SelfTradePrevention::CancelOldest,
+            1 => SelfTradePrevention::DecrementAndCancel,
+            _ => SelfTradePrevention::CancelBoth,
+        };
+        orderbook.set_self_trade_prevention(Some(mode));
+        let mut mirror = BookMirror::new();
+
+        run_random_flow(&mut orderbook, &mut rng, 1, 500, |orderbook| {
+            mirror.apply_all(&orderbook.drain_events()).unwrap();
+            assert_mirrors(orderbook, &mirror);
+        });
+        assert!(!orderbook.trades.is_empty());
+    }
+}
+
+#[test]
+fn mirror_started_from_a_snapshot() {
+    let mut rng = Rng(42);
+    let mut orderbook = OrderBook::new();
+    orderbook.set_event_recording(true);
+    run_random_flow(&mut orderbook, &mut rng, 1, 200, |_| {});
+    let snapshot = orderbook.snapshot();
+    let earlier_events = orderbook.drain_events();
+
+    let mut mirror = BookMirror::from_snapshot(&snapshot);
+    assert_mirrors(&orderbook, &mirror);
+    // events the snapshot already includes are ignored
+    mirror.apply_all(&earlier_events).unwrap();
+
+    run_random_flow(&mut orderbook, &mut rng, 201, 200, |_| {});
+    mirror.apply_all(&orderbook.drain_events()).unwrap();
+    assert_mirrors(&orderbook, &mirror);
+}
+
+#[test]
+fn gap_is_detected_and_resynced() {
+    let mut orderbook = OrderBook::new();
+    orderbook.set_event_recording(true);
+    let mut mirror = BookMirror::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(20), Side::Buy)).unwrap();
+
+    let events = orderbook.drain_events();
+    mirror.apply(&events[0]).unwrap();
+    assert_eq!(mirror.apply(&events[2]), Err(MirrorError::SequenceGap { expected: 2, received: 3 }));
+    assert!(mirror.needs_resync());
+    assert_eq!(mirror.apply(&events[1]), Err(MirrorError::NeedsResync));
+
+    mirror.resync(&orderbook.snapshot());
+    assert!(!mirror.needs_resync());
+    assert_mirrors(&orderbook, &mirror);
+    orderbook.cancel_order(1).unwrap();
+    mirror.apply_all(&orderbook.drain_events()).unwrap();
+    assert_mirrors(&orderbook, &mirror);
+}
+
+#[test]
+fn level_mismatch_is_detected() {
+    let mut mirror = BookMirror::new();
+    let event = SequencedEvent {
+        sequence: 1,
+        event: BookEvent::LevelChanged { side: Side::Buy, price: Price::from_int(20), quantity: 100, order_count: 1 },
+    };
+    assert_eq!(mirror.apply(&event), Err(MirrorError::LevelMismatch { side: Side::Buy, price: Price::from_int(20) }));
+    assert!(mirror.needs_resync());
+}
@@ -0,0 +1,60 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::depth::{Depth, DepthLevel};
+
+fn level(price: i64, quantity: usize, hidden_quantity: usize, order_count: usize) -> DepthLevel {
+    DepthLevel { price: Price::from_int(price), quantity, hidden_quantity, order_count }
+}
+
+fn book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    let orders = [
+        (1, 100, 20, Side::Buy),
+        (2, 50, 20, Side::Buy),
+        (3, 70, 19, Side::Buy),
+        (4, 10, 18, Side::Buy),
+        (5, 100, 30, Side::Sell),
+        (6, 80, 32, Side::Sell),
+    ];
+    for (id, quantity, price, side) in orders {
+        orderbook.place_order(Order::new(id, OrderType::GTC, quantity, Price::from_int(price), side)).unwrap();
+    }
+    orderbook.place_order(Order::new(7, OrderType::GTC, 500, Price::from_int(32), Side::Sell).with_display_quantity(100)).unwrap();
+    orderbook
+}
+
+#[test]
+fn depth_of_top_levels() {
+    let orderbook = book();
+    assert_eq!(orderbook.depth(2), Depth {
+        bids: vec![level(20, 150, 0, 2), level(19, 70, 0, 1)],
+        asks: vec![level(30, 100, 0, 1), level(32, 180, 400, 2)],
+    });
+}
+
+#[test]
+fn depth_beyond_the_book_returns_every_level() {
+    let orderbook = book();
+    let depth = orderbook.depth(10);
+    assert_eq!(depth.bids.len(), 3);
+    assert_eq!(depth.asks.len(), 2);
+    assert_eq!(OrderBook::new().depth(5), Depth::default());
+}
+
+#[test]
+fn depth_follows_fills_and_cancels() {
+    let mut orderbook = book();
+    orderbook.place_order(Order::new(8, OrderType::IOC, 120, Price::from_int(20), Side::Sell)).unwrap();
+    orderbook.cancel_order(4).unwrap();
+    assert_eq!(orderbook.depth(5).bids, vec![level(20, 30, 0, 1), level(19, 70, 0, 1)]);
+}
+
+#[test]
+fn full_book_in_price_order() {
+    let orderbook = book();
+    let bids: Vec<_> = orderbook.bid_depth().map(|level| level.price).collect();
+    assert_eq!(bids, vec![Price::from_int(20), Price::from_int(19), Price::from_int(18)]);
+    let asks: Vec<_> = orderbook.ask_depth().map(|level| level.price).collect();
+    assert_eq!(asks, vec![Price::from_int(30), Price::from_int(32)]);
+}
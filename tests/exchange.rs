@@ -0,0 +1,89 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, TradingRules};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::error::OrderBookError;
+use ac_rust_orderbook::exchange::{Exchange, ExchangeError, InstrumentConfig, PriceBand};
+
+fn exchange() -> Exchange {
+    let mut exchange = Exchange::new();
+    exchange.add_instrument("AAPL", InstrumentConfig::new(Price::from_raw(10_000))).unwrap();
+    exchange.add_instrument("ESZ6", InstrumentConfig::new(Price::from_raw(250_000))
+        .with_rules(TradingRules::new().with_lot_size(5))
+        .with_price_band(PriceBand::new(Price::from_int(4000), Price::from_int(6000)))).unwrap();
+    exchange
+}
+
+#[test]
+fn orders_are_routed_by_symbol() {
+    let mut exchange = exchange();
+    exchange.place_order("AAPL", Order::new(1, OrderType::GTC, 100, Price::from_int(190), Side::Sell)).unwrap();
+    exchange.place_order("ESZ6", Order::new(2, OrderType::GTC, 10, Price::from_int(5000), Side::Sell)).unwrap();
+
+    let report = exchange.place_order("AAPL", Order::new(3, OrderType::IOC, 40, Price::from_int(190), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(report.fills[0].counterparty, 1);
+    assert_eq!(exchange.book("AAPL").unwrap().trades.len(), 1);
+    assert_eq!(exchange.book("ESZ6").unwrap().trades.len(), 0);
+    assert_eq!(exchange.symbol_of(2), Some("ESZ6"));
+    assert_eq!(exchange.book("ESZ6").unwrap().tick_size(), Price::from_raw(250_000));
+}
+
+#[test]
+fn cancel_and_amend_by_id_alone() {
+    let mut exchange = exchange();
+    exchange.place_order("AAPL", Order::new(1, OrderType::GTC, 100, Price::from_int(190), Side::Sell)).unwrap();
+    exchange.place_order("ESZ6", Order::new(2, OrderType::GTC, 10, Price::from_int(5000), Side::Sell)).unwrap();
+
+    exchange.amend_order(2, Price::from_int(5001), 5).unwrap();
+    assert_eq!(exchange.book("ESZ6").unwrap().get_ask(), Some(&Price::from_int(5001)));
+    assert_eq!(exchange.cancel_order(1).unwrap().id, 1);
+    assert_eq!(exchange.book("AAPL").unwrap().get_ask(), None);
+    assert_eq!(exchange.cancel_order(1), Err(ExchangeError::UnknownOrderId(1)));
+    assert_eq!(exchange.amend_order(1, Price::from_int(100), 10), Err(ExchangeError::UnknownOrderId(1)));
+    assert_eq!(exchange.cancel_order(7), Err(ExchangeError::UnknownOrderId(7)));
+}
+
+#[test]
+fn instrument_rules_are_enforced() {
+    let mut exchange = exchange();
+    let off_lot = exchange.place_order("ESZ6", Order::new(1, OrderType::GTC, 7, Price::from_int(5000), Side::Buy));
+    assert_eq!(off_lot, Err(ExchangeError::Book(OrderBookError::OffLot(7))));
+    let outside = exchange.place_order("ESZ6", Order::new(1, OrderType::GTC, 5, Price::from_int(3000), Side::Buy));
+    assert_eq!(outside, Err(ExchangeError::OutsidePriceBand(Price::from_int(3000))));
+    let stop_outside = exchange.place_order("ESZ6", Order::new(1, OrderType::StopMarket, 5, Price::from_int(0), Side::Sell)
+        .with_stop_price(Price::from_int(3500)));
+    assert_eq!(stop_outside, Err(ExchangeError::OutsidePriceBand(Price::from_int(3500))));
+    let off_tick = exchange.place_order("ESZ6", Order::new(1, OrderType::GTC, 5, Price::from_raw(5_000_100_000), Side::Buy));
+    assert_eq!(off_tick, Err(ExchangeError::Book(OrderBookError::OffTick(Price::from_raw(5_000_100_000)))));
+    let unknown = exchange.place_order("MSFT", Order::new(1, OrderType::GTC, 5, Price::from_int(400), Side::Buy));
+    assert_eq!(unknown, Err(ExchangeError::UnknownSymbol("MSFT".to_string())));
+
+    exchange.place_order("ESZ6", Order::new(1, OrderType::GTC, 5, Price::from_int(5000), Side::Buy)).unwrap();
+    assert_eq!(exchange.amend_order(1, Price::from_int(5000), 3), Err(ExchangeError::Book(OrderBookError::OffLot(3))));
+    assert_eq!(exchange.amend_order(1, Price::from_int(7000), 5), Err(ExchangeError::OutsidePriceBand(Price::from_int(7000))));
+}
+
+#[test]
+fn order_ids_are_unique_across_instruments() {
+    let mut exchange = exchange();
+    exchange.place_order("AAPL", Order::new(1, OrderType::GTC, 100, Price::from_int(190), Side::Sell)).unwrap();
+    let duplicate = exchange.place_order("ESZ6", Order::new(1, OrderType::GTC, 5, Price::from_int(5000), Side::Buy));
+    assert_eq!(duplicate, Err(ExchangeError::DuplicateOrderId(1)));
+    assert_eq!(exchange.add_instrument("AAPL", InstrumentConfig::new(Price::from_int(1))),
+        Err(ExchangeError::DuplicateSymbol("AAPL".to_string())));
+}
+
+#[test]
+fn open_orders_of_an_account() {
+    let mut exchange = exchange();
+    exchange.place_order("AAPL", Order::new(1, OrderType::GTC, 100, Price::from_int(190), Side::Sell).with_owner(7)).unwrap();
+    exchange.place_order("AAPL", Order::new(2, OrderType::GTC, 100, Price::from_int(189), Side::Buy).with_owner(8)).unwrap();
+    exchange.place_order("ESZ6", Order::new(3, OrderType::GTC, 10, Price::from_int(5000), Side::Buy).with_owner(7)).unwrap();
+    exchange.place_order("AAPL", Order::new(4, OrderType::GTC, 50, Price::from_int(188), Side::Buy).with_owner(7)).unwrap();
+    exchange.place_order("AAPL", Order::new(5, OrderType::StopLimit, 50, Price::from_int(180), Side::Sell)
+        .with_stop_price(Price::from_int(185)).with_owner(7)).unwrap();
+
+    let open: Vec<_> = exchange.open_orders(7).iter().map(|(symbol, order)| (*symbol, order.id)).collect();
+    assert_eq!(open, vec![("AAPL", 4), ("AAPL", 1), ("AAPL", 5), ("ESZ6", 3)]);
+    assert!(exchange.open_orders(9).is_empty());
+}
@@ -0,0 +1,117 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::{OrderStatus, RejectReason};
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::error::OrderBookError;
+
+/// Asks of 30 at 10 and 40 at 11.
+fn book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 30, Price::from_int(10), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 40, Price::from_int(11), Side::Sell)).unwrap();
+    orderbook
+}
+
+#[test]
+fn min_execution_quantity_rejects_short_liquidity() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::IOC, 100, Price::from_int(11), Side::Buy)
+        .with_min_execution_quantity(80)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(report.reject_reason, Some(RejectReason::InsufficientLiquidity));
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.sell_volume, 70);
+}
+
+#[test]
+fn min_execution_quantity_met_fills_and_rests_the_rest() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(11), Side::Buy)
+        .with_min_execution_quantity(60)).unwrap();
+    assert_eq!(report.filled_quantity, 70);
+    assert_eq!(report.remaining_quantity, 30);
+    // the minimum only applies on arrival
+    let resting = orderbook.get_order(3).unwrap();
+    assert_eq!(resting.order.min_execution_quantity, None);
+}
+
+#[test]
+fn min_execution_quantity_does_not_stop_a_passive_order_resting() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(9), Side::Buy)
+        .with_min_execution_quantity(100)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(9)));
+}
+
+#[test]
+fn incoming_all_or_none_fills_completely_or_not_at_all() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 80, Price::from_int(11), Side::Buy)
+        .with_all_or_none()).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.get_bid(), None);
+
+    let report = orderbook.place_order(Order::new(4, OrderType::GTC, 70, Price::from_int(11), Side::Buy)
+        .with_all_or_none()).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+}
+
+#[test]
+fn resting_all_or_none_is_skipped_without_losing_its_place() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(10), Side::Sell).with_all_or_none()).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 50, Price::from_int(10), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(11), Side::Sell)).unwrap();
+
+    let report = orderbook.place_order(Order::new(4, OrderType::IOC, 80, Price::from_int(11), Side::Buy)).unwrap();
+    let counterparties: Vec<_> = report.fills.iter().map(|fill| (fill.counterparty, fill.quantity)).collect();
+    assert_eq!(counterparties, vec![(2, 50), (3, 30)]);
+    let resting = orderbook.get_order(1).unwrap();
+    assert_eq!(resting.order.quantity, 100);
+    assert_eq!(resting.queue_position, 0);
+
+    // an order large enough takes the all-or-none order first, in priority order
+    orderbook.place_order(Order::new(5, OrderType::GTC, 20, Price::from_int(10), Side::Sell)).unwrap();
+    let report = orderbook.place_order(Order::new(6, OrderType::IOC, 110, Price::from_int(10), Side::Buy)).unwrap();
+    let counterparties: Vec<_> = report.fills.iter().map(|fill| (fill.counterparty, fill.quantity)).collect();
+    assert_eq!(counterparties, vec![(1, 100), (5, 10)]);
+}
+
+#[test]
+fn fill_or_kill_only_counts_all_or_none_orders_it_can_fill() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(10), Side::Sell).with_all_or_none()).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 30, Price::from_int(10), Side::Sell)).unwrap();
+
+    let report = orderbook.place_order(Order::new(3, OrderType::FOK, 50, Price::from_int(10), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    let report = orderbook.place_order(Order::new(4, OrderType::FOK, 130, Price::from_int(10), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn amend_that_cannot_fill_an_all_or_none_order_leaves_it_alone() {
+    let mut orderbook = book();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(9), Side::Buy).with_all_or_none()).unwrap();
+    let report = orderbook.amend_order(3, Price::from_int(10), 50).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(report.reject_reason, Some(RejectReason::InsufficientLiquidity));
+    assert_eq!(orderbook.get_order(3).unwrap().order.price, Price::from_int(9));
+    assert_eq!(orderbook.trades.len(), 0);
+}
+
+#[test]
+fn invalid_constraints_are_refused() {
+    let mut orderbook = book();
+    let result = orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(9), Side::Buy)
+        .with_min_execution_quantity(60));
+    assert_eq!(result.unwrap_err(), OrderBookError::InvalidMinExecutionQuantity(60));
+    let result = orderbook.place_order(Order::new(4, OrderType::GTC, 50, Price::from_int(9), Side::Buy)
+        .with_min_execution_quantity(0));
+    assert_eq!(result.unwrap_err(), OrderBookError::InvalidMinExecutionQuantity(0));
+    let result = orderbook.place_order(Order::new(5, OrderType::GTC, 50, Price::from_int(9), Side::Buy)
+        .with_all_or_none().with_display_quantity(10));
+    assert_eq!(result.unwrap_err(), OrderBookError::AllOrNoneIceberg);
+}
@@ -0,0 +1,118 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::{OrderStatus, OrderExpired};
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::clock::SimulatedClock;
+use ac_rust_orderbook::error::OrderBookError;
+
+const SECOND: u64 = 1_000_000_000;
+
+fn book_at(now: u64) -> (OrderBook, SimulatedClock) {
+    let clock = SimulatedClock::new(now);
+    let mut orderbook = OrderBook::new();
+    orderbook.set_clock(clock.clone());
+    (orderbook, clock)
+}
+
+fn gtd(id: i32, expires_at: u64) -> Order {
+    Order::new(id, OrderType::GTD, 100, Price::from_int(30), Side::Sell).with_expiry(expires_at)
+}
+
+#[test]
+fn gtd_order_rests_until_expiry() {
+    let (mut orderbook, clock) = book_at(100 * SECOND);
+    let report = orderbook.place_order(gtd(1, 110 * SECOND)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+
+    clock.advance(9 * SECOND);
+    assert!(orderbook.expire_orders().is_empty());
+    assert_eq!(orderbook.get_ask(), Some(&Price::from_int(30)));
+
+    clock.advance(SECOND);
+    assert_eq!(orderbook.expire_orders(), vec![OrderExpired { order_id: 1, quantity: 100, timestamp: 110 * SECOND }]);
+    assert_eq!(orderbook.get_ask(), None);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.cancel_order(1).unwrap_err(), OrderBookError::UnknownOrderId(1));
+}
+
+#[test]
+fn expired_orders_never_trade() {
+    let (mut orderbook, clock) = book_at(100 * SECOND);
+    orderbook.place_order(gtd(1, 105 * SECOND)).unwrap();
+    orderbook.place_order(gtd(2, 103 * SECOND)).unwrap();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap();
+
+    clock.set(106 * SECOND);
+    let report = orderbook.place_order(Order::new(4, OrderType::IOC, 100, Price::from_int(31), Side::Buy)).unwrap();
+    let expired: Vec<_> = report.expired.iter().map(|expired| expired.order_id).collect();
+    assert_eq!(expired, vec![2, 1]);
+    assert_eq!(report.fills[0].counterparty, 3);
+    assert_eq!(report.fills[0].price, Price::from_int(31));
+}
+
+#[test]
+fn partially_filled_gtd_order_expires_with_its_remainder() {
+    let (mut orderbook, clock) = book_at(100 * SECOND);
+    orderbook.place_order(gtd(1, 105 * SECOND)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::IOC, 40, Price::from_int(30), Side::Buy)).unwrap();
+
+    clock.set(105 * SECOND);
+    assert_eq!(orderbook.expire_orders()[0].quantity, 60);
+}
+
+#[test]
+fn trades_are_timestamped_by_the_clock() {
+    let (mut orderbook, clock) = book_at(100 * SECOND);
+    orderbook.place_order(gtd(1, 105 * SECOND)).unwrap();
+    clock.advance(SECOND);
+    orderbook.place_order(Order::new(2, OrderType::IOC, 40, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(orderbook.trades.last().unwrap().timestamp, 101 * SECOND);
+}
+
+#[test]
+fn day_orders_expire_at_session_end() {
+    let (mut orderbook, _clock) = book_at(100 * SECOND);
+    orderbook.place_order(Order::new(2, OrderType::Day, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(1, OrderType::Day, 100, Price::from_int(20), Side::Buy)).unwrap();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap();
+
+    let expired: Vec<_> = orderbook.end_session().iter().map(|expired| expired.order_id).collect();
+    assert_eq!(expired, vec![1, 2]);
+    assert_eq!(orderbook.get_bid(), None);
+    assert_eq!(orderbook.get_ask(), Some(&Price::from_int(31)));
+}
+
+#[test]
+fn gtd_validation() {
+    let (mut orderbook, _clock) = book_at(100 * SECOND);
+    let missing = orderbook.place_order(Order::new(1, OrderType::GTD, 100, Price::from_int(30), Side::Sell));
+    assert_eq!(missing.unwrap_err(), OrderBookError::MissingExpiry);
+    assert_eq!(orderbook.place_order(gtd(1, 100 * SECOND)).unwrap_err(), OrderBookError::ExpiryInPast);
+}
+
+#[test]
+fn refused_cancel_replace_does_not_expire_orders() {
+    let (mut orderbook, clock) = book_at(100 * SECOND);
+    orderbook.place_order(gtd(1, 110 * SECOND)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap();
+    clock.advance(10 * SECOND);
+
+    let replacement = Order::new(3, OrderType::GTC, 0, Price::from_int(31), Side::Sell);
+    assert_eq!(orderbook.cancel_replace(2, replacement).unwrap_err(), OrderBookError::ZeroQuantity);
+    assert_eq!(orderbook.get_ask(), Some(&Price::from_int(30)));
+
+    let replacement = Order::new(3, OrderType::GTC, 50, Price::from_int(31), Side::Sell);
+    let report = orderbook.cancel_replace(2, replacement).unwrap();
+    assert_eq!(report.expired, vec![OrderExpired { order_id: 1, quantity: 100, timestamp: 110 * SECOND }]);
+}
+
+#[test]
+fn expired_order_cannot_be_amended_or_replaced() {
+    let (mut orderbook, clock) = book_at(100 * SECOND);
+    orderbook.place_order(gtd(1, 110 * SECOND)).unwrap();
+    clock.advance(10 * SECOND);
+
+    assert_eq!(orderbook.amend_order(1, Price::from_int(31), 100).unwrap_err(), OrderBookError::UnknownOrderId(1));
+    assert_eq!(orderbook.cancel_replace(1, gtd(2, 120 * SECOND)).unwrap_err(), OrderBookError::UnknownOrderId(1));
+    assert_eq!(orderbook.expire_orders(), vec![OrderExpired { order_id: 1, quantity: 100, timestamp: 110 * SECOND }]);
+}
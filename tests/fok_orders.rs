@@ -0,0 +1,73 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::orderbook::OrderBook;
+
+fn sell_side_book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(32), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(33), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    orderbook
+}
+
+#[test]
+fn fok_order_fills_across_levels() {
+    let mut orderbook = sell_side_book();
+
+    let order = Order::new(4, OrderType::FOK, 150, Price::from_int(32), Side::Buy);
+    assert_eq!(orderbook.place_order(order).unwrap().status, OrderStatus::Filled);
+
+    assert_eq!(orderbook.trades.len(), 2);
+    assert_eq!(orderbook.trades.between(4, 1).next().unwrap().quantity, 100);
+    assert_eq!(orderbook.trades.between(4, 2).next().unwrap().quantity, 50);
+    assert_eq!(orderbook.trades.between(4, 2).next().unwrap().price, Price::from_int(32));
+    assert_eq!(orderbook.buy_volume, 0);
+    assert_eq!(orderbook.sell_volume, 150);
+    assert_eq!(orderbook.get_ask().unwrap(), &Price::from_int(32));
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn fok_order_killed_without_enough_liquidity_in_limit() {
+    let mut orderbook = sell_side_book();
+
+    // 300 is available on the book, but only 200 at or below the limit
+    let order = Order::new(4, OrderType::FOK, 250, Price::from_int(32), Side::Buy);
+    assert_eq!(orderbook.place_order(order).unwrap().status, OrderStatus::Rejected);
+
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.buy_volume, 0);
+    assert_eq!(orderbook.sell_volume, 300);
+    assert_eq!(orderbook.get_ask().unwrap(), &Price::from_int(31));
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn fok_order_fills_exact_quantity() {
+    let mut orderbook = OrderBook::new();
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(29), Side::Buy)).unwrap().status, OrderStatus::Rested);
+
+    let order = Order::new(3, OrderType::FOK, 200, Price::from_int(29), Side::Sell);
+    assert_eq!(orderbook.place_order(order).unwrap().status, OrderStatus::Filled);
+
+    assert_eq!(orderbook.trades.len(), 2);
+    assert_eq!(orderbook.trades.between(2, 3).next().unwrap().price, Price::from_int(29));
+    assert_eq!(orderbook.buy_volume, 0);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_bid(), None);
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn fok_order_killed_on_empty_book() {
+    let mut orderbook = OrderBook::new();
+
+    let order = Order::new(1, OrderType::FOK, 100, Price::from_int(30), Side::Sell);
+    assert_eq!(orderbook.place_order(order).unwrap().status, OrderStatus::Rejected);
+
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_ask(), None);
+}
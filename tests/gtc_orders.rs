@@ -1,40 +1,157 @@
 use ac_rust_orderbook::types::{Price, Side, OrderType};
 use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::{Fill, OrderStatus};
+use ac_rust_orderbook::error::OrderBookError;
 use ac_rust_orderbook::orderbook::OrderBook;
 
 #[test]
 fn place_matching_gtc_order() {
     let mut orderbook = OrderBook::new();
 
-    let order1 = Order::new(1, OrderType::GTC, 100, Price(30.0), Side::Buy);
-    let order2 = Order::new(2, OrderType::GTC, 100, Price(29.0), Side::Buy);
-    let order3 = Order::new(3, OrderType::GTC, 100, Price(28.0), Side::Buy);
-    let order4 = Order::new(4, OrderType::GTC, 100, Price(27.0), Side::Buy);
-    let order5 = Order::new(5, OrderType::GTC, 100, Price(26.0), Side::Buy);
-    assert!(orderbook.place_order(order1));
-    assert!(orderbook.place_order(order2));
-    assert!(orderbook.place_order(order3));
-    assert!(orderbook.place_order(order4));
-    assert!(orderbook.place_order(order5));
-
-    let order6 = Order::new(6, OrderType::GTC, 100, Price(31.0), Side::Sell);
-    let order7 = Order::new(7, OrderType::GTC, 100, Price(32.0), Side::Sell);
-    let order8 = Order::new(8, OrderType::GTC, 100, Price(33.0), Side::Sell);
-    let order9 = Order::new(9, OrderType::GTC, 100, Price(34.0), Side::Sell);
-    assert!(orderbook.place_order(order6));
-    assert!(orderbook.place_order(order7));
-    assert!(orderbook.place_order(order8));
-    assert!(orderbook.place_order(order9));
+    let order1 = Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy);
+    let order2 = Order::new(2, OrderType::GTC, 100, Price::from_int(29), Side::Buy);
+    let order3 = Order::new(3, OrderType::GTC, 100, Price::from_int(28), Side::Buy);
+    let order4 = Order::new(4, OrderType::GTC, 100, Price::from_int(27), Side::Buy);
+    let order5 = Order::new(5, OrderType::GTC, 100, Price::from_int(26), Side::Buy);
+    assert_eq!(orderbook.place_order(order1).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order2).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order3).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order4).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order5).unwrap().status, OrderStatus::Rested);
+
+    let order6 = Order::new(6, OrderType::GTC, 100, Price::from_int(31), Side::Sell);
+    let order7 = Order::new(7, OrderType::GTC, 100, Price::from_int(32), Side::Sell);
+    let order8 = Order::new(8, OrderType::GTC, 100, Price::from_int(33), Side::Sell);
+    let order9 = Order::new(9, OrderType::GTC, 100, Price::from_int(34), Side::Sell);
+    assert_eq!(orderbook.place_order(order6).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order7).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order8).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(order9).unwrap().status, OrderStatus::Rested);
 
     assert_eq!(orderbook.buy_volume, 500);
     assert_eq!(orderbook.sell_volume, 400);
     
-    let order10 = Order::new(10, OrderType::GTC, 50, Price(29.0), Side::Sell);
-    orderbook.place_order(order10);
+    let order10 = Order::new(10, OrderType::GTC, 50, Price::from_int(29), Side::Sell);
+    orderbook.place_order(order10).unwrap();
 
     assert_eq!(orderbook.buy_volume, 450);
-    assert_eq!(orderbook.get_bid().unwrap(), &Price(30.0));
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(30));
     assert_eq!(orderbook.trades.len(), 1);
-    assert_eq!(orderbook.trades.get(&(1, 10)).unwrap().quantity, 50);
-    assert_eq!(orderbook.trades.get(&(1, 10)).unwrap().price, Price(30.0));
-}
\ No newline at end of file
+    assert_eq!(orderbook.trades.between(1, 10).next().unwrap().quantity, 50);
+    assert_eq!(orderbook.trades.between(1, 10).next().unwrap().price, Price::from_int(30));
+}
+
+#[test]
+fn gtc_order_sweeps_multiple_levels() {
+    let mut orderbook = OrderBook::new();
+
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 50, Price::from_int(31), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(32), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(4, OrderType::GTC, 100, Price::from_int(34), Side::Sell)).unwrap().status, OrderStatus::Rested);
+
+    // crosses the 31 and 32 levels, the remainder rests at 33
+    let report = orderbook.place_order(Order::new(5, OrderType::GTC, 300, Price::from_int(33), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::PartiallyFilled);
+    assert_eq!(report.fills, vec![
+        Fill { counterparty: 1, price: Price::from_int(31), quantity: 100 },
+        Fill { counterparty: 2, price: Price::from_int(31), quantity: 50 },
+        Fill { counterparty: 3, price: Price::from_int(32), quantity: 100 },
+    ]);
+    assert_eq!(report.filled_quantity, 250);
+    assert_eq!(report.remaining_quantity, 50);
+
+    assert_eq!(orderbook.trades.len(), 3);
+    assert_eq!(orderbook.trades.between(5, 1).next().unwrap().quantity, 100);
+    assert_eq!(orderbook.trades.between(5, 2).next().unwrap().quantity, 50);
+    assert_eq!(orderbook.trades.between(5, 3).next().unwrap().quantity, 100);
+    assert_eq!(orderbook.trades.between(5, 3).next().unwrap().price, Price::from_int(32));
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(33));
+    assert_eq!(orderbook.get_ask().unwrap(), &Price::from_int(34));
+    assert_eq!(orderbook.buy_volume, 50);
+    assert_eq!(orderbook.sell_volume, 100);
+}
+
+#[test]
+fn partially_filled_resting_order_keeps_priority() {
+    let mut orderbook = OrderBook::new();
+
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap().status, OrderStatus::Rested);
+
+    assert_eq!(orderbook.place_order(Order::new(3, OrderType::GTC, 40, Price::from_int(30), Side::Sell)).unwrap().status, OrderStatus::Filled);
+    assert_eq!(orderbook.place_order(Order::new(4, OrderType::GTC, 80, Price::from_int(30), Side::Sell)).unwrap().status, OrderStatus::Filled);
+
+    assert_eq!(orderbook.trades.between(1, 3).next().unwrap().quantity, 40);
+    assert_eq!(orderbook.trades.between(1, 4).next().unwrap().quantity, 60);
+    assert_eq!(orderbook.trades.between(2, 4).next().unwrap().quantity, 20);
+    assert_eq!(orderbook.buy_volume, 80);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(30));
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn sweep_skips_cancelled_levels() {
+    let mut orderbook = OrderBook::new();
+
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(32), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(33), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    orderbook.cancel_order(2).unwrap();
+
+    assert_eq!(orderbook.place_order(Order::new(4, OrderType::GTC, 150, Price::from_int(33), Side::Buy)).unwrap().status, OrderStatus::Filled);
+
+    assert_eq!(orderbook.trades.len(), 2);
+    assert_eq!(orderbook.trades.between(4, 3).next().unwrap().quantity, 50);
+    assert_eq!(orderbook.sell_volume, 50);
+    assert_eq!(orderbook.buy_volume, 0);
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn off_tick_order_is_rejected() {
+    let mut orderbook = OrderBook::with_tick_size("0.05".parse().unwrap());
+
+    let off_tick = "30.01".parse().unwrap();
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, off_tick, Side::Buy)), Err(OrderBookError::OffTick(off_tick)));
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, "30.05".parse().unwrap(), Side::Buy)).unwrap().status, OrderStatus::Rested);
+
+    assert_eq!(orderbook.buy_volume, 100);
+    assert_eq!(orderbook.get_bid().unwrap(), &"30.05".parse().unwrap());
+}
+
+#[test]
+fn levels_iterate_in_price_order() {
+    let mut orderbook = OrderBook::new();
+    for (id, quantity, price, side) in [
+        (1, 100, 29, Side::Buy), (2, 50, 30, Side::Buy), (3, 25, 29, Side::Buy),
+        (4, 100, 32, Side::Sell), (5, 100, 31, Side::Sell), (6, 10, 32, Side::Sell),
+    ] {
+        orderbook.place_order(Order::new(id, OrderType::GTC, quantity, Price::from_int(price), side)).unwrap();
+    }
+
+    let bids: Vec<_> = orderbook.bid_levels().collect();
+    let asks: Vec<_> = orderbook.ask_levels().collect();
+    assert_eq!(bids, vec![(Price::from_int(30), 50), (Price::from_int(29), 125)]);
+    assert_eq!(asks, vec![(Price::from_int(31), 100), (Price::from_int(32), 110)]);
+
+    orderbook.cancel_order(2).unwrap();
+    assert_eq!(orderbook.bid_levels().count(), 1);
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(29));
+}
+
+#[test]
+fn cancel_from_middle_of_queue_keeps_priority() {
+    let mut orderbook = OrderBook::new();
+    for id in 1..=3 {
+        orderbook.place_order(Order::new(id, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+    }
+    orderbook.cancel_order(2).unwrap();
+
+    let report = orderbook.place_order(Order::new(4, OrderType::GTC, 150, Price::from_int(30), Side::Sell)).unwrap();
+    let counterparties: Vec<_> = report.fills.iter().map(|fill| fill.counterparty).collect();
+    assert_eq!(counterparties, vec![1, 3]);
+    assert_eq!(orderbook.buy_volume, 50);
+    assert_eq!(orderbook.bid_levels().collect::<Vec<_>>(), vec![(Price::from_int(30), 50)]);
+}
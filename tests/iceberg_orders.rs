@@ -0,0 +1,109 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::error::OrderBookError;
+
+fn iceberg(id: i32, quantity: usize, peak: usize) -> Order {
+    Order::new(id, OrderType::GTC, quantity, Price::from_int(30), Side::Sell).with_display_quantity(peak)
+}
+
+#[test]
+fn only_the_peak_is_displayed() {
+    let mut orderbook = OrderBook::new();
+    let report = orderbook.place_order(iceberg(1, 1000, 100)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(report.remaining_quantity, 1000);
+    assert_eq!(orderbook.ask_levels().collect::<Vec<_>>(), vec![(Price::from_int(30), 100)]);
+    assert_eq!(orderbook.sell_volume, 100);
+    assert_eq!(orderbook.sell_hidden_volume, 900);
+}
+
+#[test]
+fn replenished_peak_goes_to_the_back_of_the_queue() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(iceberg(1, 300, 100)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 150, Price::from_int(30), Side::Buy)).unwrap();
+    let counterparties: Vec<_> = report.fills.iter().map(|fill| (fill.counterparty, fill.quantity)).collect();
+    assert_eq!(counterparties, vec![(1, 100), (2, 50)]);
+    assert_eq!(orderbook.ask_levels().collect::<Vec<_>>(), vec![(Price::from_int(30), 150)]);
+    assert_eq!(orderbook.sell_volume, 150);
+    assert_eq!(orderbook.sell_hidden_volume, 100);
+
+    // order 2 is now ahead of the replenished peak
+    let report = orderbook.place_order(Order::new(4, OrderType::GTC, 60, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(report.fills[0].counterparty, 2);
+    assert_eq!(report.fills[1].counterparty, 1);
+}
+
+#[test]
+fn same_orders_trade_once_per_peak() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(iceberg(1, 250, 100)).unwrap();
+
+    let report = orderbook.place_order(Order::new(2, OrderType::GTC, 250, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    let quantities: Vec<_> = orderbook.trades.between(2, 1).map(|trade| trade.quantity).collect();
+    assert_eq!(quantities, vec![100, 100, 50]);
+    assert_eq!(orderbook.get_ask(), None);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.sell_hidden_volume, 0);
+}
+
+#[test]
+fn cancel_removes_the_reserve() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(iceberg(1, 1000, 100)).unwrap();
+
+    let cancelled = orderbook.cancel_order(1).unwrap();
+    assert_eq!(cancelled.open_quantity(), 1000);
+    assert_eq!(orderbook.get_ask(), None);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.sell_hidden_volume, 0);
+}
+
+#[test]
+fn quantity_decrease_takes_from_the_reserve_first() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(iceberg(1, 1000, 100)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+
+    let report = orderbook.amend_order(1, Price::from_int(30), 150).unwrap();
+    assert_eq!(report.remaining_quantity, 150);
+    assert_eq!(orderbook.sell_volume, 200);
+    assert_eq!(orderbook.sell_hidden_volume, 50);
+    let report = orderbook.place_order(Order::new(3, OrderType::IOC, 10, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(report.fills[0].counterparty, 1);
+}
+
+#[test]
+fn fill_or_kill_counts_hidden_quantity() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(iceberg(1, 500, 100)).unwrap();
+
+    let report = orderbook.place_order(Order::new(2, OrderType::FOK, 400, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(orderbook.sell_volume, 100);
+    assert_eq!(orderbook.sell_hidden_volume, 0);
+}
+
+#[test]
+fn incoming_iceberg_rests_its_remainder_as_an_iceberg() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+
+    let report = orderbook.place_order(Order::new(2, OrderType::GTC, 500, Price::from_int(30), Side::Sell)
+        .with_display_quantity(50)).unwrap();
+    assert_eq!(report.filled_quantity, 100);
+    assert_eq!(report.remaining_quantity, 400);
+    assert_eq!(orderbook.sell_volume, 50);
+    assert_eq!(orderbook.sell_hidden_volume, 350);
+}
+
+#[test]
+fn zero_display_quantity_is_refused() {
+    let mut orderbook = OrderBook::new();
+    assert_eq!(orderbook.place_order(iceberg(1, 1000, 0)).unwrap_err(), OrderBookError::ZeroDisplayQuantity);
+}
@@ -0,0 +1,87 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, TradingRules};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::error::OrderBookError;
+
+/// Lots of 10, between 20 and 1000 per order, and at most 5000 of notional.
+fn book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.set_trading_rules(TradingRules::new()
+        .with_lot_size(10)
+        .with_min_quantity(20)
+        .with_max_quantity(1000)
+        .with_max_notional(Price::from_int(5000)));
+    orderbook
+}
+
+#[test]
+fn orders_within_the_rules_are_accepted() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(1, OrderType::GTC, 500, Price::from_int(10), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(orderbook.buy_volume, 500);
+}
+
+#[test]
+fn quantity_must_be_a_whole_number_of_lots() {
+    let mut orderbook = book();
+    let result = orderbook.place_order(Order::new(1, OrderType::GTC, 25, Price::from_int(10), Side::Buy));
+    assert_eq!(result.unwrap_err(), OrderBookError::OffLot(25));
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn iceberg_peak_must_be_a_whole_number_of_lots() {
+    let mut orderbook = book();
+    let result = orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(10), Side::Buy)
+        .with_display_quantity(15));
+    assert_eq!(result.unwrap_err(), OrderBookError::OffLot(15));
+}
+
+#[test]
+fn quantity_limits_are_enforced() {
+    let mut orderbook = book();
+    let small = orderbook.place_order(Order::new(1, OrderType::GTC, 10, Price::from_int(1), Side::Buy));
+    assert_eq!(small.unwrap_err(), OrderBookError::BelowMinQuantity(10));
+    let large = orderbook.place_order(Order::new(2, OrderType::GTC, 1010, Price::from_int(1), Side::Buy));
+    assert_eq!(large.unwrap_err(), OrderBookError::AboveMaxQuantity(1010));
+    assert!(orderbook.place_order(Order::new(3, OrderType::GTC, 1000, Price::from_int(1), Side::Buy)).is_ok());
+}
+
+#[test]
+fn notional_limit_applies_to_limit_orders_only() {
+    let mut orderbook = book();
+    let result = orderbook.place_order(Order::new(1, OrderType::GTC, 510, Price::from_int(10), Side::Sell));
+    assert_eq!(result.unwrap_err(), OrderBookError::AboveMaxNotional(Price::from_int(5100)));
+
+    orderbook.place_order(Order::new(2, OrderType::GTC, 1000, Price::from_int(5), Side::Sell)).unwrap();
+    let report = orderbook.place_order(Order::new(3, OrderType::Market, 600, Price::from_int(0), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+}
+
+#[test]
+fn amends_are_checked_against_the_rules() {
+    let mut orderbook = book();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(10), Side::Buy)).unwrap();
+
+    assert_eq!(orderbook.amend_order(1, Price::from_int(10), 55).unwrap_err(), OrderBookError::OffLot(55));
+    assert_eq!(orderbook.amend_order(1, Price::from_int(10), 10).unwrap_err(), OrderBookError::BelowMinQuantity(10));
+    assert_eq!(orderbook.amend_order(1, Price::from_int(10), 155).unwrap_err(), OrderBookError::OffLot(155));
+    assert_eq!(orderbook.amend_order(1, Price::from_int(20), 300).unwrap_err(),
+               OrderBookError::AboveMaxNotional(Price::from_int(6000)));
+    assert_eq!(orderbook.buy_volume, 100);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(10)));
+
+    let replacement = Order::new(2, OrderType::GTC, 2000, Price::from_int(1), Side::Buy);
+    assert_eq!(orderbook.cancel_replace(1, replacement).unwrap_err(), OrderBookError::AboveMaxQuantity(2000));
+    assert_eq!(orderbook.buy_volume, 100);
+
+    // a remainder below the minimum can still be repriced, but not resized off-lot
+    orderbook.place_order(Order::new(3, OrderType::GTC, 90, Price::from_int(10), Side::Sell)).unwrap();
+    let report = orderbook.amend_order(1, Price::from_int(9), 10).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(9)));
+    assert_eq!(orderbook.amend_order(1, Price::from_int(9), 5).unwrap_err(), OrderBookError::OffLot(5));
+    assert_eq!(orderbook.amend_order(1, Price::from_int(9), 15).unwrap_err(), OrderBookError::OffLot(15));
+}
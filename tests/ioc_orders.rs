@@ -0,0 +1,65 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::orderbook::OrderBook;
+
+fn buy_side_book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(29), Side::Buy)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(28), Side::Buy)).unwrap().status, OrderStatus::Rested);
+    orderbook
+}
+
+#[test]
+fn ioc_order_fills_completely() {
+    let mut orderbook = buy_side_book();
+
+    let order = Order::new(4, OrderType::IOC, 150, Price::from_int(29), Side::Sell);
+    let report = orderbook.place_order(order).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(report.filled_quantity, 150);
+    assert_eq!(report.cancelled_quantity, 0);
+
+    assert_eq!(orderbook.trades.len(), 2);
+    assert_eq!(orderbook.trades.between(1, 4).next().unwrap().quantity, 100);
+    assert_eq!(orderbook.trades.between(2, 4).next().unwrap().quantity, 50);
+    assert_eq!(orderbook.buy_volume, 150);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(29));
+}
+
+#[test]
+fn ioc_order_cancels_remainder_past_limit() {
+    let mut orderbook = buy_side_book();
+
+    let order = Order::new(4, OrderType::IOC, 250, Price::from_int(29), Side::Sell);
+    let report = orderbook.place_order(order).unwrap();
+    assert_eq!(report.status, OrderStatus::Cancelled);
+    assert_eq!(report.filled_quantity, 200);
+    assert_eq!(report.cancelled_quantity, 50);
+    assert_eq!(report.remaining_quantity, 0);
+
+    assert_eq!(orderbook.trades.len(), 2);
+    assert_eq!(orderbook.trades.between(2, 4).next().unwrap().quantity, 100);
+    assert_eq!(orderbook.buy_volume, 100);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(28));
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn ioc_order_without_cross_is_cancelled() {
+    let mut orderbook = buy_side_book();
+
+    let order = Order::new(4, OrderType::IOC, 100, Price::from_int(31), Side::Sell);
+    let report = orderbook.place_order(order).unwrap();
+    assert_eq!(report.status, OrderStatus::Cancelled);
+    assert_eq!(report.filled_quantity, 0);
+    assert_eq!(report.cancelled_quantity, 100);
+
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.buy_volume, 300);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_ask(), None);
+}
@@ -1,5 +1,6 @@
 use ac_rust_orderbook::types::{Price, Side, OrderType};
-use ac_rust_orderbook::order::{self, Order};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
 use ac_rust_orderbook::orderbook::{OrderBook};
 
 #[test]
@@ -9,22 +10,40 @@ fn buyside_market_order() {
         id: 1,
         kind: OrderType::Market,
         quantity: 100,
-        price: Price(100.0),
+        price: Price::from_int(100),
         side: Side::Buy,
+        owner: None,
+        stop_price: None,
+        trail: None,
+        display_quantity: None,
+        hidden_quantity: 0,
+        post_only: None,
+        expires_at: None,
+        min_execution_quantity: None,
+        all_or_none: false,
     };
     let sell_order: Order = Order {
         id: 2,
         kind: OrderType::GTC,
         quantity: 100,
-        price: Price(100.0),
+        price: Price::from_int(100),
         side: Side::Sell,
+        owner: None,
+        stop_price: None,
+        trail: None,
+        display_quantity: None,
+        hidden_quantity: 0,
+        post_only: None,
+        expires_at: None,
+        min_execution_quantity: None,
+        all_or_none: false,
     };
-    orderbook.place_order(sell_order);
+    orderbook.place_order(sell_order).unwrap();
 
     assert_eq!(0, orderbook.trades.len());
     assert_eq!(100, orderbook.sell_volume);
 
-    orderbook.place_order(buy_order);
+    orderbook.place_order(buy_order).unwrap();
 
     assert_eq!(1, orderbook.trades.len());
     assert_eq!(0, orderbook.buy_volume);
@@ -37,22 +56,94 @@ fn sellside_market_order() {
         id: 1,
         kind: OrderType::GTC,
         quantity: 100,
-        price: Price(100.0),
+        price: Price::from_int(100),
         side: Side::Buy,
+        owner: None,
+        stop_price: None,
+        trail: None,
+        display_quantity: None,
+        hidden_quantity: 0,
+        post_only: None,
+        expires_at: None,
+        min_execution_quantity: None,
+        all_or_none: false,
     };
     let sell_order: Order = Order {
         id: 2,
         kind: OrderType::Market,
         quantity: 100,
-        price: Price(0.0),
+        price: Price::ZERO,
         side: Side::Sell,
+        owner: None,
+        stop_price: None,
+        trail: None,
+        display_quantity: None,
+        hidden_quantity: 0,
+        post_only: None,
+        expires_at: None,
+        min_execution_quantity: None,
+        all_or_none: false,
     };
-    assert!(orderbook.place_order(buy_order));
-    assert!(orderbook.place_order(sell_order));
+    assert_eq!(orderbook.place_order(buy_order).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(sell_order).unwrap().status, OrderStatus::Filled);
 
     assert_eq!(1, orderbook.trades.len());
     assert_eq!(0, orderbook.sell_volume);
     assert_eq!(0, orderbook.buy_volume);
-    assert_eq!(100, orderbook.trades.get(&(1, 2)).unwrap().quantity);
-    assert_eq!(Price(100.0), orderbook.trades.get(&(1, 2)).unwrap().price);
-}
\ No newline at end of file
+    assert_eq!(100, orderbook.trades.between(1, 2).next().unwrap().quantity);
+    assert_eq!(Price::from_int(100), orderbook.trades.between(1, 2).next().unwrap().price);
+}
+#[test]
+fn market_order_sweeps_levels_and_cancels_remainder() {
+    let mut orderbook: OrderBook = OrderBook::new();
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Sell)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(101), Side::Sell)).unwrap().status, OrderStatus::Rested);
+
+    let report = orderbook.place_order(Order::new(3, OrderType::Market, 250, Price::ZERO, Side::Buy)).unwrap();
+    assert_eq!(OrderStatus::Cancelled, report.status);
+    assert_eq!(200, report.filled_quantity);
+    assert_eq!(50, report.cancelled_quantity);
+
+    assert_eq!(2, orderbook.trades.len());
+    assert_eq!(Price::from_int(101), orderbook.trades.between(3, 2).next().unwrap().price);
+    assert_eq!(0, orderbook.buy_volume);
+    assert_eq!(0, orderbook.sell_volume);
+    assert_eq!(None, orderbook.get_bid());
+    assert_eq!(None, orderbook.get_ask());
+}
+
+#[test]
+fn market_order_on_empty_book_is_cancelled() {
+    let mut orderbook: OrderBook = OrderBook::new();
+
+    let report = orderbook.place_order(Order::new(1, OrderType::Market, 100, Price::ZERO, Side::Sell)).unwrap();
+    assert_eq!(OrderStatus::Cancelled, report.status);
+    assert_eq!(100, report.cancelled_quantity);
+
+    assert_eq!(0, orderbook.trades.len());
+    assert_eq!(0, orderbook.sell_volume);
+    assert_eq!(None, orderbook.get_ask());
+}
+
+#[test]
+fn market_order_stops_at_protection_limit() {
+    let mut orderbook: OrderBook = OrderBook::new();
+    orderbook.set_market_protection(Some(5));
+    let level_2: Price = "99.95".parse().unwrap();
+    let level_3: Price = "99.9".parse().unwrap();
+    assert_eq!(orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(100), Side::Buy)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(2, OrderType::GTC, 100, level_2, Side::Buy)).unwrap().status, OrderStatus::Rested);
+    assert_eq!(orderbook.place_order(Order::new(3, OrderType::GTC, 100, level_3, Side::Buy)).unwrap().status, OrderStatus::Rested);
+
+    let report = orderbook.place_order(Order::new(4, OrderType::Market, 300, Price::ZERO, Side::Sell)).unwrap();
+    assert_eq!(OrderStatus::Cancelled, report.status);
+    assert_eq!(200, report.filled_quantity);
+    assert_eq!(100, report.cancelled_quantity);
+
+    assert_eq!(2, orderbook.trades.len());
+    assert_eq!(level_2, orderbook.trades.between(2, 4).next().unwrap().price);
+    assert_eq!(100, orderbook.buy_volume);
+    assert_eq!(0, orderbook.sell_volume);
+    assert_eq!(&level_3, orderbook.get_bid().unwrap());
+    assert_eq!(None, orderbook.get_ask());
+}
@@ -0,0 +1,72 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::error::OrderBookError;
+use ac_rust_orderbook::orderbook::OrderBook;
+
+#[test]
+fn duplicate_order_id_is_rejected() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+
+    let result = orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(31), Side::Sell));
+    assert_eq!(result, Err(OrderBookError::DuplicateOrderId(1)));
+    assert_eq!(orderbook.buy_volume, 100);
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn zero_quantity_is_rejected() {
+    let mut orderbook = OrderBook::new();
+
+    let result = orderbook.place_order(Order::new(1, OrderType::GTC, 0, Price::from_int(30), Side::Buy));
+    assert_eq!(result, Err(OrderBookError::ZeroQuantity));
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn non_positive_price_is_rejected() {
+    let mut orderbook = OrderBook::new();
+
+    let result = orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::ZERO, Side::Buy));
+    assert_eq!(result, Err(OrderBookError::InvalidPrice(Price::ZERO)));
+    let result = orderbook.place_order(Order::new(2, OrderType::IOC, 100, Price::from_int(-5), Side::Sell));
+    assert_eq!(result, Err(OrderBookError::InvalidPrice(Price::from_int(-5))));
+    assert_eq!(orderbook.get_bid(), None);
+    assert_eq!(orderbook.get_ask(), None);
+}
+
+#[test]
+fn cancel_unknown_order() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+
+    assert_eq!(orderbook.cancel_order(2), Err(OrderBookError::UnknownOrderId(2)));
+    let cancelled = orderbook.cancel_order(1).unwrap();
+    assert_eq!(cancelled.quantity, 100);
+    assert_eq!(orderbook.cancel_order(1), Err(OrderBookError::UnknownOrderId(1)));
+    assert_eq!(orderbook.buy_volume, 0);
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn halted_book_rejects_orders_but_allows_cancels() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).unwrap();
+    orderbook.halt();
+
+    let result = orderbook.place_order(Order::new(2, OrderType::Market, 100, Price::ZERO, Side::Sell));
+    assert_eq!(result, Err(OrderBookError::BookHalted));
+    assert_eq!(orderbook.trades.len(), 0);
+    assert!(orderbook.cancel_order(1).is_ok());
+
+    orderbook.resume();
+    assert!(!orderbook.is_halted());
+    assert!(orderbook.place_order(Order::new(3, OrderType::GTC, 100, Price::from_int(30), Side::Buy)).is_ok());
+}
+
+#[test]
+fn error_messages() {
+    assert_eq!(OrderBookError::UnknownOrderId(7).to_string(), "order id 7 is not on the book");
+    assert_eq!(OrderBookError::OffTick("1.005".parse().unwrap()).to_string(), "price 1.005 is not a multiple of the tick size");
+}
@@ -0,0 +1,60 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::orderbook::OrderBook;
+
+fn book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    let orders = [
+        (1, 100, 20, Side::Buy),
+        (2, 50, 20, Side::Buy),
+        (3, 70, 20, Side::Buy),
+        (4, 10, 19, Side::Buy),
+        (5, 100, 31, Side::Sell),
+        (6, 80, 30, Side::Sell),
+    ];
+    for (id, quantity, price, side) in orders {
+        orderbook.place_order(Order::new(id, OrderType::GTC, quantity, Price::from_int(price), side)).unwrap();
+    }
+    orderbook
+}
+
+#[test]
+fn get_order_reports_queue_position() {
+    let orderbook = book();
+    let resting = orderbook.get_order(3).unwrap();
+    assert_eq!(resting.order.quantity, 70);
+    assert_eq!(resting.queue_position, 2);
+    assert_eq!(resting.quantity_ahead, 150);
+
+    let front = orderbook.get_order(1).unwrap();
+    assert_eq!(front.queue_position, 0);
+    assert_eq!(front.quantity_ahead, 0);
+    assert_eq!(orderbook.get_order(99), None);
+}
+
+#[test]
+fn get_order_follows_fills_and_cancels() {
+    let mut orderbook = book();
+    orderbook.place_order(Order::new(7, OrderType::IOC, 120, Price::from_int(20), Side::Sell)).unwrap();
+    let resting = orderbook.get_order(3).unwrap();
+    assert_eq!(resting.queue_position, 1);
+    assert_eq!(resting.quantity_ahead, 30);
+
+    orderbook.cancel_order(2).unwrap();
+    let resting = orderbook.get_order(3).unwrap();
+    assert_eq!(resting.queue_position, 0);
+    assert_eq!(resting.order.quantity, 70);
+    assert_eq!(orderbook.get_order(1), None);
+}
+
+#[test]
+fn orders_are_walked_in_price_time_priority() {
+    let orderbook = book();
+    let bids: Vec<_> = orderbook.bid_orders().map(|order| order.id).collect();
+    assert_eq!(bids, vec![1, 2, 3, 4]);
+    let asks: Vec<_> = orderbook.ask_orders().map(|order| order.id).collect();
+    assert_eq!(asks, vec![6, 5]);
+    let level: Vec<_> = orderbook.orders_at(Side::Buy, Price::from_int(20)).map(|order| order.quantity).collect();
+    assert_eq!(level, vec![100, 50, 70]);
+    assert_eq!(orderbook.orders_at(Side::Sell, Price::from_int(20)).count(), 0);
+}
@@ -0,0 +1,72 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, PostOnly};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::{OrderStatus, RejectReason};
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::error::OrderBookError;
+
+/// A bid at 20 and an ask at 30.
+fn book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(20), Side::Buy)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook
+}
+
+#[test]
+fn post_only_rests_when_not_crossing() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(25), Side::Buy)
+        .with_post_only(PostOnly::Reject)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(report.repriced, None);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(25)));
+}
+
+#[test]
+fn crossing_post_only_is_rejected() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(30), Side::Buy)
+        .with_post_only(PostOnly::Reject)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(report.reject_reason, Some(RejectReason::WouldTakeLiquidity));
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.buy_volume, 100);
+    assert_eq!(orderbook.sell_volume, 100);
+}
+
+#[test]
+fn crossing_post_only_slides_behind_the_touch() {
+    let mut orderbook = book();
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(35), Side::Buy)
+        .with_post_only(PostOnly::Slide)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(report.repriced, Some(Price::from_raw(29_990_000)));
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_raw(29_990_000)));
+    assert_eq!(orderbook.trades.len(), 0);
+
+    let report = orderbook.place_order(Order::new(4, OrderType::GTC, 50, Price::from_int(15), Side::Sell)
+        .with_post_only(PostOnly::Slide)).unwrap();
+    assert_eq!(report.repriced, Some(Price::from_int(30)));
+    assert_eq!(orderbook.ask_levels().next(), Some((Price::from_int(30), 150)));
+}
+
+#[test]
+fn amended_post_only_does_not_take_liquidity() {
+    let mut orderbook = book();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(25), Side::Buy)
+        .with_post_only(PostOnly::Reject)).unwrap();
+
+    let report = orderbook.amend_order(3, Price::from_int(30), 50).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(25)));
+    assert_eq!(orderbook.buy_volume, 150);
+}
+
+#[test]
+fn post_only_needs_an_order_that_rests() {
+    let mut orderbook = book();
+    let result = orderbook.place_order(Order::new(3, OrderType::IOC, 50, Price::from_int(25), Side::Buy)
+        .with_post_only(PostOnly::Reject));
+    assert_eq!(result.unwrap_err(), OrderBookError::PostOnlyCannotRest);
+}
@@ -0,0 +1,169 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, SelfTradePrevention};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::{OrderStatus, SelfTradePrevented};
+use ac_rust_orderbook::orderbook::OrderBook;
+
+const FIRM: u64 = 7;
+const OTHER_FIRM: u64 = 8;
+
+/// Two asks at 30: an order of FIRM at the front of the queue, then one of OTHER_FIRM.
+fn book_with_own_order_in_front(mode: SelfTradePrevention) -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.set_self_trade_prevention(Some(mode));
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell).with_owner(FIRM)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Sell).with_owner(OTHER_FIRM)).unwrap();
+    orderbook
+}
+
+#[test]
+fn cancel_newest() {
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::CancelNewest);
+
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 150, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Cancelled);
+    assert_eq!(report.fills.len(), 0);
+    assert_eq!(report.cancelled_quantity, 150);
+    assert_eq!(report.self_trade_prevented, vec![SelfTradePrevented {
+        resting_order: 1,
+        mode: SelfTradePrevention::CancelNewest,
+        incoming_cancelled: 150,
+        resting_cancelled: 0,
+    }]);
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.sell_volume, 200);
+    assert_eq!(orderbook.buy_volume, 0);
+    assert_eq!(orderbook.get_bid(), None);
+}
+
+#[test]
+fn cancel_oldest() {
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::CancelOldest);
+
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 150, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::PartiallyFilled);
+    assert_eq!(report.filled_quantity, 100);
+    assert_eq!(report.fills[0].counterparty, 2);
+    assert_eq!(report.remaining_quantity, 50);
+    assert_eq!(report.cancelled_quantity, 0);
+    assert_eq!(report.self_trade_prevented[0].resting_cancelled, 100);
+    assert!(orderbook.cancel_order(1).is_err());
+    assert_eq!(orderbook.sell_volume, 0);
+    assert_eq!(orderbook.buy_volume, 50);
+    assert_eq!(orderbook.get_bid().unwrap(), &Price::from_int(30));
+}
+
+#[test]
+fn cancel_both() {
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::CancelBoth);
+
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 150, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Cancelled);
+    assert_eq!(report.cancelled_quantity, 150);
+    assert_eq!(report.self_trade_prevented[0].resting_cancelled, 100);
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.sell_volume, 100);
+    assert_eq!(orderbook.ask_levels().collect::<Vec<_>>(), vec![(Price::from_int(30), 100)]);
+}
+
+#[test]
+fn decrement_and_cancel() {
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::DecrementAndCancel);
+
+    // the resting order is larger, so it is decremented and the incoming order cancelled
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 40, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Cancelled);
+    assert_eq!(report.cancelled_quantity, 40);
+    assert_eq!(report.self_trade_prevented[0].resting_cancelled, 40);
+    assert_eq!(orderbook.sell_volume, 160);
+
+    // now the incoming order is larger, the resting order is cancelled and matching goes on
+    let report = orderbook.place_order(Order::new(4, OrderType::GTC, 100, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.self_trade_prevented[0].incoming_cancelled, 60);
+    assert_eq!(report.self_trade_prevented[0].resting_cancelled, 60);
+    assert_eq!(report.filled_quantity, 40);
+    assert_eq!(report.fills[0].counterparty, 2);
+    assert_eq!(report.cancelled_quantity, 60);
+    assert_eq!(report.status, OrderStatus::Cancelled);
+    assert_eq!(orderbook.trades.len(), 1);
+    assert_eq!(orderbook.sell_volume, 60);
+    assert_eq!(orderbook.buy_volume, 0);
+
+    // a remainder that rests keeps the order live even though part of it was cancelled
+    orderbook.place_order(Order::new(5, OrderType::GTC, 60, Price::from_int(30), Side::Buy)).unwrap();
+    orderbook.place_order(Order::new(6, OrderType::GTC, 30, Price::from_int(30), Side::Sell).with_owner(FIRM)).unwrap();
+    let report = orderbook.place_order(Order::new(7, OrderType::GTC, 100, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.cancelled_quantity, 30);
+    assert_eq!(report.remaining_quantity, 70);
+    assert_eq!(report.status, OrderStatus::Rested);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(30)));
+    assert_eq!(orderbook.buy_volume, 70);
+}
+
+#[test]
+fn different_owners_and_anonymous_orders_trade() {
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::CancelBoth);
+
+    let report = orderbook.place_order(Order::new(3, OrderType::GTC, 50, Price::from_int(30), Side::Buy).with_owner(OTHER_FIRM + 1)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    let report = orderbook.place_order(Order::new(4, OrderType::GTC, 50, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert!(report.self_trade_prevented.is_empty());
+    assert_eq!(orderbook.trades.by_order(1).count(), 2);
+}
+
+#[test]
+fn prevention_disabled_by_default() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell).with_owner(FIRM)).unwrap();
+
+    let report = orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+}
+
+#[test]
+fn fok_does_not_count_own_liquidity() {
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::CancelOldest);
+
+    let report = orderbook.place_order(Order::new(3, OrderType::FOK, 150, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.sell_volume, 200);
+
+    let report = orderbook.place_order(Order::new(4, OrderType::FOK, 100, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(report.self_trade_prevented.len(), 1);
+    assert_eq!(orderbook.sell_volume, 0);
+
+    let mut orderbook = book_with_own_order_in_front(SelfTradePrevention::CancelNewest);
+    let report = orderbook.place_order(Order::new(3, OrderType::FOK, 50, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.sell_volume, 200);
+}
+
+#[test]
+fn fill_or_kill_counts_iceberg_peaks_in_the_order_they_show() {
+    // the iceberg's next peak queues behind the own order, which would cancel the incoming order
+    let iceberg_ahead_of_own_order = || {
+        let mut orderbook = OrderBook::new();
+        orderbook.set_self_trade_prevention(Some(SelfTradePrevention::CancelNewest));
+        orderbook.place_order(Order::new(1, OrderType::GTC, 8, Price::from_int(30), Side::Buy)
+            .with_owner(OTHER_FIRM).with_display_quantity(1)).unwrap();
+        orderbook.place_order(Order::new(2, OrderType::GTC, 10, Price::from_int(30), Side::Buy).with_owner(FIRM)).unwrap();
+        orderbook
+    };
+
+    let mut orderbook = iceberg_ahead_of_own_order();
+    let report = orderbook.place_order(Order::new(3, OrderType::FOK, 5, Price::from_int(30), Side::Sell).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.trades.len(), 0);
+
+    let mut orderbook = iceberg_ahead_of_own_order();
+    let report = orderbook.place_order(Order::new(3, OrderType::IOC, 6, Price::from_int(30), Side::Sell)
+        .with_owner(FIRM).with_min_execution_quantity(5)).unwrap();
+    assert_eq!(report.status, OrderStatus::Rejected);
+    assert_eq!(orderbook.trades.len(), 0);
+    assert_eq!(orderbook.buy_volume, 11);
+
+    // the first peak alone is enough
+    let report = orderbook.place_order(Order::new(4, OrderType::FOK, 1, Price::from_int(30), Side::Sell).with_owner(FIRM)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+}
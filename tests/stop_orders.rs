@@ -0,0 +1,147 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::error::OrderBookError;
+
+/// Asks of 100 at 30, 31 and 32, bids of 100 at 20, 19 and 18, and one trade at 25.
+fn book_with_last_trade_at_25() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    for (id, price) in [(1, 30), (2, 31), (3, 32)] {
+        orderbook.place_order(Order::new(id, OrderType::GTC, 100, Price::from_int(price), Side::Sell)).unwrap();
+    }
+    for (id, price) in [(4, 20), (5, 19), (6, 18)] {
+        orderbook.place_order(Order::new(id, OrderType::GTC, 100, Price::from_int(price), Side::Buy)).unwrap();
+    }
+    orderbook.place_order(Order::new(7, OrderType::GTC, 10, Price::from_int(25), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(8, OrderType::IOC, 10, Price::from_int(25), Side::Buy)).unwrap();
+    assert_eq!(orderbook.last_trade_price(), Some(Price::from_int(25)));
+    orderbook
+}
+
+#[test]
+fn stop_waits_off_book() {
+    let mut orderbook = book_with_last_trade_at_25();
+
+    let report = orderbook.place_order(Order::new(10, OrderType::StopMarket, 50, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(30))).unwrap();
+    assert_eq!(report.status, OrderStatus::Pending);
+    assert_eq!(report.remaining_quantity, 50);
+    assert!(orderbook.pending_stop(10).is_some());
+    assert_eq!(orderbook.buy_volume, 300);
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(20)));
+}
+
+#[test]
+fn buy_stop_market_triggers_on_trade_at_stop_price() {
+    let mut orderbook = book_with_last_trade_at_25();
+    orderbook.place_order(Order::new(10, OrderType::StopMarket, 150, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(30))).unwrap();
+
+    let report = orderbook.place_order(Order::new(11, OrderType::IOC, 50, Price::from_int(30), Side::Buy)).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(report.triggered.len(), 1);
+    let triggered = &report.triggered[0];
+    assert_eq!(triggered.order_id, 10);
+    assert_eq!(triggered.status, OrderStatus::Filled);
+    assert_eq!(triggered.fills[0].price, Price::from_int(30));
+    assert_eq!(triggered.fills[1].price, Price::from_int(31));
+    assert!(orderbook.pending_stop(10).is_none());
+    assert_eq!(orderbook.last_trade_price(), Some(Price::from_int(31)));
+}
+
+#[test]
+fn sell_stop_limit_rests_at_its_limit_when_triggered() {
+    let mut orderbook = book_with_last_trade_at_25();
+    orderbook.place_order(Order::new(10, OrderType::StopLimit, 200, Price::from_int(19), Side::Sell)
+        .with_stop_price(Price::from_int(20))).unwrap();
+
+    let report = orderbook.place_order(Order::new(11, OrderType::IOC, 50, Price::from_int(20), Side::Sell)).unwrap();
+    let triggered = &report.triggered[0];
+    assert_eq!(triggered.status, OrderStatus::PartiallyFilled);
+    assert_eq!(triggered.filled_quantity, 150);
+    assert_eq!(triggered.remaining_quantity, 50);
+    assert_eq!(orderbook.get_ask(), Some(&Price::from_int(19)));
+    assert_eq!(orderbook.get_bid(), Some(&Price::from_int(18)));
+}
+
+#[test]
+fn stop_not_triggered_by_trade_short_of_stop_price() {
+    let mut orderbook = book_with_last_trade_at_25();
+    orderbook.place_order(Order::new(10, OrderType::StopMarket, 50, Price::ZERO, Side::Sell)
+        .with_stop_price(Price::from_int(19))).unwrap();
+
+    let report = orderbook.place_order(Order::new(11, OrderType::IOC, 100, Price::from_int(20), Side::Sell)).unwrap();
+    assert!(report.triggered.is_empty());
+    assert!(orderbook.pending_stop(10).is_some());
+}
+
+#[test]
+fn stop_already_through_is_placed_immediately() {
+    let mut orderbook = book_with_last_trade_at_25();
+
+    let report = orderbook.place_order(Order::new(10, OrderType::StopMarket, 50, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(24))).unwrap();
+    assert_eq!(report.status, OrderStatus::Filled);
+    assert_eq!(report.fills[0].price, Price::from_int(30));
+    assert!(orderbook.pending_stop(10).is_none());
+}
+
+#[test]
+fn triggered_stops_release_in_arrival_order() {
+    let mut orderbook = book_with_last_trade_at_25();
+    orderbook.place_order(Order::new(10, OrderType::StopMarket, 100, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(30))).unwrap();
+    orderbook.place_order(Order::new(11, OrderType::StopMarket, 100, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(26))).unwrap();
+
+    let report = orderbook.place_order(Order::new(12, OrderType::IOC, 10, Price::from_int(30), Side::Buy)).unwrap();
+    let ids: Vec<_> = report.triggered.iter().map(|triggered| triggered.order_id).collect();
+    assert_eq!(ids, vec![10, 11]);
+    assert_eq!(report.triggered[0].fills[0].price, Price::from_int(30));
+    assert_eq!(report.triggered[1].fills[0].price, Price::from_int(31));
+}
+
+#[test]
+fn triggered_stop_cascades_into_further_stops() {
+    let mut orderbook = book_with_last_trade_at_25();
+    orderbook.place_order(Order::new(10, OrderType::StopMarket, 100, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(31))).unwrap();
+    orderbook.place_order(Order::new(11, OrderType::StopMarket, 100, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(30))).unwrap();
+
+    // trades at 30 trigger 11, whose fill at 31 triggers 10
+    let report = orderbook.place_order(Order::new(12, OrderType::IOC, 10, Price::from_int(30), Side::Buy)).unwrap();
+    let ids: Vec<_> = report.triggered.iter().map(|triggered| triggered.order_id).collect();
+    assert_eq!(ids, vec![11, 10]);
+    assert_eq!(report.triggered[1].fills[0].price, Price::from_int(31));
+    assert_eq!(report.triggered[1].fills[1].price, Price::from_int(32));
+}
+
+#[test]
+fn cancel_pending_stop() {
+    let mut orderbook = book_with_last_trade_at_25();
+    orderbook.place_order(Order::new(10, OrderType::StopMarket, 50, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(30))).unwrap();
+
+    assert_eq!(orderbook.cancel_order(10).unwrap().id, 10);
+    assert!(orderbook.pending_stop(10).is_none());
+    let report = orderbook.place_order(Order::new(11, OrderType::IOC, 50, Price::from_int(30), Side::Buy)).unwrap();
+    assert!(report.triggered.is_empty());
+}
+
+#[test]
+fn stop_validation() {
+    let mut orderbook = book_with_last_trade_at_25();
+
+    let missing = orderbook.place_order(Order::new(10, OrderType::StopMarket, 50, Price::ZERO, Side::Buy));
+    assert_eq!(missing.unwrap_err(), OrderBookError::MissingStopPrice);
+    let off_tick = orderbook.place_order(Order::new(10, OrderType::StopLimit, 50, Price::from_int(31), Side::Buy)
+        .with_stop_price(Price::from_raw(30_001_000)));
+    assert_eq!(off_tick.unwrap_err(), OrderBookError::OffTick(Price::from_raw(30_001_000)));
+
+    orderbook.place_order(Order::new(10, OrderType::StopMarket, 50, Price::ZERO, Side::Buy)
+        .with_stop_price(Price::from_int(30))).unwrap();
+    let duplicate = orderbook.place_order(Order::new(10, OrderType::GTC, 50, Price::from_int(20), Side::Buy));
+    assert_eq!(duplicate.unwrap_err(), OrderBookError::DuplicateOrderId(10));
+}
@@ -0,0 +1,67 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::orderbook::OrderBook;
+
+#[test]
+fn trades_are_looked_up_by_order() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(30), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(2, OrderType::GTC, 100, Price::from_int(31), Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(3, OrderType::GTC, 140, Price::from_int(30), Side::Buy)).unwrap();
+    orderbook.place_order(Order::new(4, OrderType::GTC, 60, Price::from_int(31), Side::Buy)).unwrap();
+    orderbook.amend_order(3, Price::from_int(31), 40).unwrap();
+
+    let trades: Vec<_> = orderbook.trades.iter().map(|trade| (trade.buy_order.id, trade.sell_order.id, trade.quantity)).collect();
+    assert_eq!(trades, vec![(3, 1, 100), (4, 2, 60), (3, 2, 40)]);
+
+    let ids = |id| orderbook.trades.by_order(id).map(|trade| trade.id).collect::<Vec<_>>();
+    assert_eq!(ids(1), vec![1]);
+    assert_eq!(ids(2), vec![2, 3]);
+    assert_eq!(ids(3), vec![1, 3]);
+    assert_eq!(ids(4), vec![2]);
+    assert_eq!(ids(5), Vec::<u64>::new());
+    assert_eq!(orderbook.trades.between(4, 2).next().unwrap().id, 2);
+    assert_eq!(orderbook.trades.between(2, 4).next(), None);
+}
+
+#[test]
+fn trade_ids_sequences_and_timestamps_increase() {
+    let mut orderbook = OrderBook::new();
+    for id in 1..=5 {
+        orderbook.place_order(Order::new(id, OrderType::GTC, 10, Price::from_int(30 + id as i64), Side::Sell)).unwrap();
+    }
+    orderbook.place_order(Order::new(6, OrderType::GTC, 50, Price::from_int(40), Side::Buy)).unwrap();
+
+    let trades: Vec<_> = orderbook.trades.iter().collect();
+    assert_eq!(trades.len(), 5);
+    for (index, pair) in trades.windows(2).enumerate() {
+        assert_eq!(pair[0].id, index as u64 + 1);
+        assert_eq!(pair[1].id, pair[0].id + 1);
+        assert!(pair[1].sequence > pair[0].sequence);
+        assert!(pair[1].timestamp >= pair[0].timestamp);
+    }
+    assert_eq!(orderbook.trades.last().unwrap().sequence, orderbook.sequence());
+    assert_eq!(orderbook.trades.next_id(), 6);
+}
+
+#[test]
+fn lookup_by_id_and_range() {
+    let mut orderbook = OrderBook::new();
+    for id in 1..=5 {
+        orderbook.place_order(Order::new(id, OrderType::GTC, 10, Price::from_int(30 + id as i64), Side::Sell)).unwrap();
+    }
+    orderbook.place_order(Order::new(6, OrderType::GTC, 50, Price::from_int(40), Side::Buy)).unwrap();
+
+    assert_eq!(orderbook.trades.get(0), None);
+    assert_eq!(orderbook.trades.get(3).unwrap().sell_order.id, 3);
+    assert_eq!(orderbook.trades.get(6), None);
+
+    let ids = |trades: &[ac_rust_orderbook::trade::Trade]| trades.iter().map(|trade| trade.id).collect::<Vec<_>>();
+    assert_eq!(ids(orderbook.trades.range(2..4)), vec![2, 3]);
+    assert_eq!(ids(orderbook.trades.range(2..=4)), vec![2, 3, 4]);
+    assert_eq!(ids(orderbook.trades.range(4..)), vec![4, 5]);
+    assert_eq!(ids(orderbook.trades.range(..)), vec![1, 2, 3, 4, 5]);
+    assert_eq!(ids(orderbook.trades.range(..=1)), vec![1]);
+    assert_eq!(ids(orderbook.trades.range(7..9)), Vec::<u64>::new());
+    assert_eq!(ids(orderbook.trades.range((std::ops::Bound::Excluded(4), std::ops::Bound::Unbounded))), vec![5]);
+}
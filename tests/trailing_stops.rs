@@ -0,0 +1,120 @@
+use ac_rust_orderbook::types::{Price, Side, OrderType, Trail, TrailOffset, TrailReference};
+use ac_rust_orderbook::order::Order;
+use ac_rust_orderbook::report::OrderStatus;
+use ac_rust_orderbook::orderbook::OrderBook;
+use ac_rust_orderbook::error::OrderBookError;
+
+/// Trades one lot at `price` between orders `id` and `id + 1`.
+fn trade_at(orderbook: &mut OrderBook, id: i32, price: Price) {
+    orderbook.place_order(Order::new(id, OrderType::GTC, 1, price, Side::Sell)).unwrap();
+    orderbook.place_order(Order::new(id + 1, OrderType::IOC, 1, price, Side::Buy)).unwrap();
+}
+
+fn trailing_sell(id: i32, kind: OrderType, trail: Trail) -> Order {
+    Order::new(id, kind, 50, Price::ZERO, Side::Sell).with_trail(trail)
+}
+
+/// A bid of 100 at 20 and a last trade at 25.
+fn book() -> OrderBook {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(20), Side::Buy)).unwrap();
+    trade_at(&mut orderbook, 2, Price::from_int(25));
+    orderbook
+}
+
+#[test]
+fn sell_trail_follows_last_trade_up_only() {
+    let mut orderbook = book();
+    let trail = Trail::new(TrailOffset::Amount(Price::from_int(2)), TrailReference::LastTrade);
+    let report = orderbook.place_order(trailing_sell(10, OrderType::TrailingStopMarket, trail)).unwrap();
+    assert_eq!(report.status, OrderStatus::Pending);
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(23)));
+
+    trade_at(&mut orderbook, 20, Price::from_int(27));
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(25)));
+    trade_at(&mut orderbook, 22, Price::from_int(26));
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(25)));
+
+    orderbook.place_order(Order::new(24, OrderType::GTC, 1, Price::from_int(25), Side::Sell)).unwrap();
+    let report = orderbook.place_order(Order::new(25, OrderType::IOC, 1, Price::from_int(25), Side::Buy)).unwrap();
+    assert_eq!(report.triggered.len(), 1);
+    assert_eq!(report.triggered[0].order_id, 10);
+    assert_eq!(report.triggered[0].status, OrderStatus::Filled);
+    assert_eq!(report.triggered[0].fills[0].price, Price::from_int(20));
+    assert_eq!(orderbook.trigger_price(10), None);
+}
+
+#[test]
+fn buy_trail_by_percentage() {
+    let mut orderbook = OrderBook::new();
+    orderbook.place_order(Order::new(1, OrderType::GTC, 100, Price::from_int(120), Side::Sell)).unwrap();
+    trade_at(&mut orderbook, 2, Price::from_int(100));
+    let trail = Trail::new(TrailOffset::BasisPoints(500), TrailReference::LastTrade);
+    orderbook.place_order(Order::new(10, OrderType::TrailingStopMarket, 50, Price::ZERO, Side::Buy).with_trail(trail)).unwrap();
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(105)));
+
+    trade_at(&mut orderbook, 20, Price::from_int(90));
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_raw(94_500_000)));
+    trade_at(&mut orderbook, 22, Price::from_int(93));
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_raw(94_500_000)));
+
+    orderbook.place_order(Order::new(24, OrderType::GTC, 1, Price::from_int(95), Side::Sell)).unwrap();
+    let report = orderbook.place_order(Order::new(25, OrderType::IOC, 1, Price::from_int(95), Side::Buy)).unwrap();
+    assert_eq!(report.triggered[0].order_id, 10);
+    assert_eq!(report.triggered[0].fills[0].price, Price::from_int(120));
+}
+
+#[test]
+fn sell_trail_follows_best_bid() {
+    let mut orderbook = book();
+    let trail = Trail::new(TrailOffset::Amount(Price::from_int(1)), TrailReference::BestPrice);
+    orderbook.place_order(trailing_sell(10, OrderType::TrailingStopMarket, trail)).unwrap();
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(19)));
+
+    orderbook.place_order(Order::new(11, OrderType::GTC, 10, Price::from_int(22), Side::Buy)).unwrap();
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(21)));
+    orderbook.cancel_order(11).unwrap();
+    orderbook.place_order(Order::new(12, OrderType::GTC, 10, Price::from_int(18), Side::Buy)).unwrap();
+    assert_eq!(orderbook.trigger_price(10), Some(Price::from_int(21)));
+
+    let report = orderbook.place_order(Order::new(13, OrderType::IOC, 10, Price::from_int(20), Side::Sell)).unwrap();
+    assert_eq!(report.triggered[0].order_id, 10);
+    assert_eq!(report.triggered[0].filled_quantity, 50);
+}
+
+#[test]
+fn trailing_stop_limit_prices_off_its_stop() {
+    let mut orderbook = book();
+    let trail = Trail::new(TrailOffset::Amount(Price::from_int(2)), TrailReference::LastTrade)
+        .with_limit_offset(Price::from_int(1));
+    orderbook.place_order(trailing_sell(10, OrderType::TrailingStopLimit, trail)).unwrap();
+    trade_at(&mut orderbook, 20, Price::from_int(27));
+
+    let report = orderbook.place_order(Order::new(22, OrderType::GTC, 1, Price::from_int(24), Side::Sell)).unwrap();
+    assert!(report.triggered.is_empty());
+    let report = orderbook.place_order(Order::new(23, OrderType::IOC, 1, Price::from_int(24), Side::Buy)).unwrap();
+    let triggered = &report.triggered[0];
+    assert_eq!(triggered.order_id, 10);
+    assert_eq!(triggered.status, OrderStatus::Rested);
+    assert_eq!(orderbook.get_ask(), Some(&Price::from_int(24)));
+    assert_eq!(orderbook.sell_volume, 50);
+}
+
+#[test]
+fn trailing_stop_validation() {
+    let mut orderbook = OrderBook::new();
+    let trail = Trail::new(TrailOffset::Amount(Price::from_int(2)), TrailReference::LastTrade);
+
+    let missing_trail = orderbook.place_order(Order::new(1, OrderType::TrailingStopMarket, 50, Price::ZERO, Side::Sell));
+    assert_eq!(missing_trail.unwrap_err(), OrderBookError::MissingTrail);
+    let bad_percentage = Trail::new(TrailOffset::BasisPoints(0), TrailReference::LastTrade);
+    let invalid = orderbook.place_order(trailing_sell(1, OrderType::TrailingStopMarket, bad_percentage));
+    assert_eq!(invalid.unwrap_err(), OrderBookError::InvalidTrail);
+    let no_reference = orderbook.place_order(trailing_sell(1, OrderType::TrailingStopMarket, trail));
+    assert_eq!(no_reference.unwrap_err(), OrderBookError::MissingStopPrice);
+
+    let report = orderbook.place_order(trailing_sell(1, OrderType::TrailingStopMarket, trail)
+        .with_stop_price(Price::from_int(10))).unwrap();
+    assert_eq!(report.status, OrderStatus::Pending);
+    assert_eq!(orderbook.trigger_price(1), Some(Price::from_int(10)));
+}